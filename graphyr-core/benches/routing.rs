@@ -0,0 +1,118 @@
+// routes the arrows of a generated document the way every painted frame did before the
+// routes were cached, and with a `Router` that keeps them between the frames
+//
+// cargo bench -p graphyr-core --bench routing -- [size] [arrows]
+
+use std::{collections::HashMap, time::Instant};
+
+use graphyr_core::{
+    cell::{CellId, CellPos},
+    configuration::arrow::{Arrow, Routing},
+    export::layout::Layout,
+    routing::{compute_path, Router, Segment},
+    Data,
+};
+use kurbo::Rect;
+
+const FRAMES: u32 = 10;
+
+fn main() {
+    let mut args = std::env::args().skip(1).filter(|arg| arg != "--bench");
+    let size = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(8);
+    let count = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(40);
+
+    let (data, arrows) = document(size, count);
+    let layout = Layout::compute(&data.cell, true);
+    let positions = layout.positions();
+    let boxes = layout.boxes();
+    println!(
+        "{} cells, {} arrows, {FRAMES} frames",
+        positions.len(),
+        arrows.len()
+    );
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        for arrow in &arrows {
+            route_uncached(&positions, arrow);
+        }
+    }
+    let uncached = start.elapsed() / FRAMES;
+    println!("uncached:          {uncached:?} per frame");
+
+    // the arrows are painted together, in lanes with hops
+    let routed = arrows
+        .iter()
+        .map(|arrow| (arrow, Routing::Direct, None))
+        .collect::<Vec<_>>();
+    let start = Instant::now();
+    let mut router = Router::new(positions.clone(), boxes.clone());
+    router.route_all(&routed);
+    let first = start.elapsed();
+    println!("router, 1st frame: {first:?}");
+
+    let start = Instant::now();
+    for _ in 1..FRAMES {
+        router.set_positions(positions.clone(), boxes.clone());
+        router.route_all(&routed);
+    }
+    let next = start.elapsed() / (FRAMES - 1);
+    println!("router, next ones: {next:?} per frame");
+    println!(
+        "speedup: {:.1}x on the 1st frame, {:.0}x on the next ones",
+        uncached.as_secs_f64() / first.as_secs_f64(),
+        uncached.as_secs_f64() / next.as_secs_f64()
+    );
+}
+
+// a table of size x size cells with arrows between cells spread over it
+fn document(size: usize, count: usize) -> (Data, Vec<Arrow>) {
+    let mut data = Data::new();
+    data.cell.create_table();
+    let cells = &mut data.cell.table.as_mut().unwrap().cells;
+    for i in 1..size {
+        cells.add_row(i);
+        cells.add_col(i);
+    }
+    for (row_id, row) in cells.data.iter_mut().enumerate() {
+        for (col_id, cell) in row.iter_mut().enumerate() {
+            cell.title = format!("cell {row_id}:{col_id}");
+        }
+    }
+
+    let ids = (0..size * size)
+        .map(|i| {
+            let pos = CellPos::new().child(i / size, i % size);
+            data.get_cell(&pos).unwrap().id.clone()
+        })
+        .collect::<Vec<_>>();
+    let arrows = (0..count)
+        .map(|i| {
+            let from = ids[i * 7 % ids.len()].clone();
+            let to = ids[(i * 13 + ids.len() / 2) % ids.len()].clone();
+            Arrow::new(from, to)
+        })
+        .filter(|arrow| arrow.from != arrow.to)
+        .collect();
+    (data, arrows)
+}
+
+// what `Main::paint` did for every arrow
+fn route_uncached(positions: &HashMap<CellId, Rect>, arrow: &Arrow) {
+    let (Some(from), Some(to)) = (positions.get(&arrow.from), positions.get(&arrow.to)) else {
+        return;
+    };
+    let center = Segment {
+        p1: from.center(),
+        p2: to.center(),
+    };
+    let (Some(start), Some(goal)) = (center.intersect_rect(from), center.intersect_rect(to)) else {
+        return;
+    };
+    let rest = positions
+        .iter()
+        .filter(|(id, _)| **id != arrow.from && **id != arrow.to)
+        .map(|(_, rect)| *rect)
+        .collect::<Vec<_>>();
+    compute_path(&rest, &start, &goal, arrow.padding);
+}
@@ -0,0 +1,209 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::table::Table;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cell {
+    pub title: String,
+    pub id: CellId,
+    pub table: Option<Table>,
+    pub hierarchy_depth: usize,
+}
+
+impl Cell {
+    pub fn new(table: Option<Table>, hierarchy_depth: usize) -> Self {
+        Self {
+            title: String::new(),
+            id: CellId::new(),
+            table,
+            hierarchy_depth,
+        }
+    }
+
+    pub fn create_table(&mut self) {
+        self.table = Some(Table::new(self.hierarchy_depth + 1));
+    }
+
+    pub fn remove_table(&mut self) {
+        self.table = None;
+    }
+
+    pub fn get_inner_cell(&self, id_ref: CellIdSlice) -> Option<&Cell> {
+        if id_ref.is_empty() {
+            return Some(self);
+        }
+
+        let (row, col) = id_ref.top();
+        self.table
+            .as_ref()?
+            .cells
+            .get(row, col)?
+            .get_inner_cell(id_ref.lower())
+    }
+
+    pub fn get_inner_cell_mut(&mut self, id_ref: CellIdSlice) -> Option<&mut Cell> {
+        if id_ref.is_empty() {
+            return Some(self);
+        }
+
+        let (row, col) = id_ref.top();
+        self.table
+            .as_mut()?
+            .cells
+            .get_mut(row, col)?
+            .get_inner_cell_mut(id_ref.lower())
+    }
+
+    // calls `f` for this cell and every cell below it
+    pub fn for_each_cell(&self, f: &mut impl FnMut(&Cell)) {
+        f(self);
+        if let Some(table) = &self.table {
+            for cell in table.cells.data.iter().flatten() {
+                cell.for_each_cell(f);
+            }
+        }
+    }
+
+    // finds the position of a cell with the given id, relative to this cell
+    pub fn find_pos(&self, id: &CellId, pos: CellPos) -> Option<CellPos> {
+        if &self.id == id {
+            return Some(pos);
+        }
+
+        let table = self.table.as_ref()?;
+        for (row_id, row) in table.cells.data.iter().enumerate() {
+            for (col_id, cell) in row.iter().enumerate() {
+                if let Some(pos) = cell.find_pos(id, pos.child(row_id, col_id)) {
+                    return Some(pos);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CellId(Uuid);
+
+impl CellId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for CellId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for CellId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// points to a single position (row, col) in the entire hierarchy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellPos(Vec<(usize, usize)>);
+
+impl CellPos {
+    pub fn new() -> Self {
+        Self(vec![(0, 0)])
+    }
+
+    // position of a cell inside of the table of this one
+    pub fn child(&self, row: usize, col: usize) -> Self {
+        let mut res = self.clone();
+        res.0.push((row, col));
+        res
+    }
+
+    // position of the cell containing this one, None for the highest cell
+    pub fn parent(&self) -> Option<Self> {
+        if self.0.len() > 1 {
+            Some(Self(self.0[..self.0.len() - 1].to_vec()))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.len() == 1
+    }
+
+    // the position inside of the parent table
+    pub fn last(&self) -> (usize, usize) {
+        self.0[self.0.len() - 1]
+    }
+
+    // all positions from the highest cell down to this one (inclusive)
+    pub fn ancestors(&self) -> Vec<CellPos> {
+        (1..=self.0.len())
+            .map(|len| Self(self.0[..len].to_vec()))
+            .collect()
+    }
+
+    // points to the highest cell
+    pub fn top(&self) -> (usize, usize) {
+        self.0[0]
+    }
+
+    // get the lower part of the id
+    pub fn lower(&self) -> CellIdSlice<'_> {
+        CellIdSlice(&self.0[1..])
+    }
+}
+
+impl Default for CellPos {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// written as "row,col/row,col" for the cells below the highest one, empty for the highest one
+impl Display for CellPos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = self.0[1..]
+            .iter()
+            .map(|(row, col)| format!("{row},{col}"))
+            .collect::<Vec<_>>();
+        f.write_str(&parts.join("/"))
+    }
+}
+
+impl FromStr for CellPos {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut res = Self::new();
+        for part in s.split('/').filter(|part| !part.is_empty()) {
+            let (row, col) = part
+                .split_once(',')
+                .with_context(|| format!("expected \"row,col\", got \"{part}\""))?;
+            res.0.push((row.trim().parse()?, col.trim().parse()?));
+        }
+        Ok(res)
+    }
+}
+
+// a slice of CellId
+pub struct CellIdSlice<'a>(&'a [(usize, usize)]);
+
+impl CellIdSlice<'_> {
+    pub fn top(&self) -> (usize, usize) {
+        self.0[0]
+    }
+
+    pub fn lower(&self) -> CellIdSlice<'_> {
+        CellIdSlice(&self.0[1..])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
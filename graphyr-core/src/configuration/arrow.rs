@@ -0,0 +1,227 @@
+use std::{fmt::Display, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use super::palette::Palette;
+use crate::{cell::CellId, color::Color};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Arrow {
+    pub from: CellId,
+    pub to: CellId,
+    pub color: Color,
+    pub padding: f64,
+    // text painted along the arrow, empty for none
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub style: LineStyle,
+    #[serde(default = "default_width")]
+    pub width: f64,
+    // the heads at the `from` and `to` ends, both are set for bidirectional arrows
+    #[serde(default)]
+    pub start: Head,
+    #[serde(default = "default_end")]
+    pub end: Head,
+    // overrides the routing of the layer when set
+    #[serde(default)]
+    pub routing: Option<Routing>,
+    // what the `from` and `to` ends are attached to
+    #[serde(default)]
+    pub from_anchor: Anchor,
+    #[serde(default)]
+    pub to_anchor: Anchor,
+    // points the arrow goes through, in order, placed by hand over the automatic route
+    #[serde(default)]
+    pub waypoints: Vec<Waypoint>,
+}
+
+impl Arrow {
+    // the padding of new arrows is picked in this range by `Configuration::add_arrow`
+    pub const PADDINGS: Range<f64> = 3.0..15.0;
+
+    // an arrow with the first color of the default palette, see `Configuration::add_arrow`
+    // for the ones of the layers
+    pub fn new(from: CellId, to: CellId) -> Self {
+        Self {
+            from,
+            to,
+            color: Palette::default().colors()[0],
+            padding: 9.,
+            label: String::new(),
+            style: LineStyle::default(),
+            width: default_width(),
+            start: Head::None,
+            end: default_end(),
+            routing: None,
+            from_anchor: Anchor::default(),
+            to_anchor: Anchor::default(),
+            waypoints: vec![],
+        }
+    }
+
+    // switches to the next color of the palette that is readable on the background
+    pub fn recolor(&mut self, palette: Palette, background: Color) {
+        self.color = palette.next(self.color, background);
+    }
+
+    // swaps the ends, the heads stay at the start and the end, so it points the other way
+    pub fn reverse(&mut self) {
+        std::mem::swap(&mut self.from, &mut self.to);
+        std::mem::swap(&mut self.from_anchor, &mut self.to_anchor);
+        self.waypoints.reverse();
+    }
+}
+
+// arrows written before the styles existed were drawn like this
+pub(crate) fn default_width() -> f64 {
+    2.
+}
+
+pub(crate) fn default_end() -> Head {
+    Head::Filled
+}
+
+// a point relative to the whole box of a cell, so it moves with the cell when the layout
+// changes
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Waypoint {
+    pub cell: CellId,
+    // 0 is the left (top) side of the box, 1 the right (bottom) one
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineStyle {
+    pub const ALL: [Self; 3] = [Self::Solid, Self::Dashed, Self::Dotted];
+
+    // lengths of the drawn and skipped parts of the line, empty for a solid one
+    pub fn dashes(self, width: f64) -> Vec<f64> {
+        match self {
+            Self::Solid => vec![],
+            Self::Dashed => vec![3. * width, 2. * width],
+            Self::Dotted => vec![width, width],
+        }
+    }
+}
+
+impl Display for LineStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Solid => "solid",
+            Self::Dashed => "dashed",
+            Self::Dotted => "dotted",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Head {
+    #[default]
+    None,
+    // two lines
+    Open,
+    // a triangle
+    Filled,
+    Diamond,
+    // the "many" end of an ER relationship
+    CrowsFoot,
+    Circle,
+}
+
+impl Head {
+    pub const ALL: [Self; 6] = [
+        Self::None,
+        Self::Open,
+        Self::Filled,
+        Self::Diamond,
+        Self::CrowsFoot,
+        Self::Circle,
+    ];
+}
+
+impl Display for Head {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Open => "open",
+            Self::Filled => "filled",
+            Self::Diamond => "diamond",
+            Self::CrowsFoot => "crow's foot",
+            Self::Circle => "circle",
+        })
+    }
+}
+
+// how the path of an arrow is found
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Routing {
+    // straight segments in any direction
+    #[default]
+    Direct,
+    // only horizontal and vertical segments with rounded corners
+    Orthogonal,
+    // a smooth curve through the points of the direct route
+    Curved,
+}
+
+impl Routing {
+    pub const ALL: [Self; 3] = [Self::Direct, Self::Orthogonal, Self::Curved];
+}
+
+impl Display for Routing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Direct => "direct",
+            Self::Orthogonal => "orthogonal",
+            Self::Curved => "curved",
+        })
+    }
+}
+
+// the part of a cell an end of an arrow is attached to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Anchor {
+    // the title, even when the cell contains more
+    #[default]
+    Title,
+    // the border of the whole cell, wherever it's closest to the other end
+    Cell,
+    // the middle of a side of the whole cell
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Anchor {
+    pub const ALL: [Self; 6] = [
+        Self::Title,
+        Self::Cell,
+        Self::Top,
+        Self::Bottom,
+        Self::Left,
+        Self::Right,
+    ];
+}
+
+impl Display for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Title => "title",
+            Self::Cell => "cell",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::Left => "left",
+            Self::Right => "right",
+        })
+    }
+}
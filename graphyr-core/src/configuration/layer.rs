@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    arrow::{Arrow, Routing},
+    palette::Palette,
+};
+use crate::color::Color;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Layer {
+    pub name: String,
+    pub enabled: bool,
+    pub arrows: Vec<Arrow>,
+    // used by the arrows that don't choose their own
+    #[serde(default)]
+    pub routing: Routing,
+    // arrows between the cells of the same two tables share one thicker trunk
+    #[serde(default)]
+    pub bundled: bool,
+    // the colors of the new arrows
+    #[serde(default)]
+    pub palette: Palette,
+}
+
+impl Layer {
+    pub fn new() -> Self {
+        Self {
+            name: "Unnamed".into(),
+            enabled: false,
+            arrows: vec![],
+            routing: Routing::default(),
+            bundled: false,
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn routing_of(&self, arrow: &Arrow) -> Routing {
+        arrow.routing.unwrap_or(self.routing)
+    }
+
+    // the color of the next arrow, the one of the palette the arrows use the least
+    pub fn next_color(&self, background: Color) -> Color {
+        let used = self
+            .arrows
+            .iter()
+            .map(|arrow| arrow.color)
+            .collect::<Vec<_>>();
+        self.palette.pick(&used, background)
+    }
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+use arrow::Arrow;
+use layer::Layer;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{cell::CellId, color::Color};
+
+pub mod arrow;
+pub mod layer;
+pub mod palette;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Configuration {
+    pub show_border: bool,
+    pub show_panes: bool,
+    pub layers: Vec<Layer>,
+    // new arrows get their random values from it, so making the same edits gives the same
+    // arrows (the cells get new ids anyway), they are random every time when it's not set
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl Configuration {
+    pub fn new() -> Self {
+        Self {
+            show_border: true,
+            show_panes: true,
+            layers: vec![Layer::new()],
+            seed: None,
+        }
+    }
+
+    // new arrows are added to every enabled layer, with the next color of its palette that
+    // can be read on the background
+    pub fn add_arrow(&mut self, from: CellId, to: CellId, background: Color) {
+        let padding = self.rng().random_range(Arrow::PADDINGS);
+        for layer in self.layers.iter_mut().filter(|layer| layer.enabled) {
+            let arrow = Arrow {
+                color: layer.next_color(background),
+                padding,
+                ..Arrow::new(from.clone(), to.clone())
+            };
+            layer.arrows.push(arrow);
+        }
+    }
+
+    // keyed by the seed together with the number of arrows, so every new arrow gets other
+    // values, and no other seed gives the same ones
+    fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => {
+                let arrows = self
+                    .layers
+                    .iter()
+                    .map(|layer| layer.arrows.len())
+                    .sum::<usize>();
+                let mut key = <StdRng as SeedableRng>::Seed::default();
+                key[..8].copy_from_slice(&seed.to_le_bytes());
+                key[8..16].copy_from_slice(&(arrows as u64).to_le_bytes());
+                StdRng::from_seed(key)
+            }
+            None => StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    // the arrow goes to the end of the other layer
+    pub fn move_arrow(&mut self, layer: usize, arrow: usize, to: usize) {
+        let exists = self
+            .layers
+            .get(layer)
+            .is_some_and(|l| arrow < l.arrows.len());
+        if exists && to < self.layers.len() {
+            let arrow = self.layers[layer].arrows.remove(arrow);
+            self.layers[to].arrows.push(arrow);
+        }
+    }
+
+    // the layer takes the place of `to`, the ones in between move by one, it's painted
+    // over the layers before it
+    pub fn move_layer(&mut self, layer: usize, to: usize) {
+        if layer < self.layers.len() && to < self.layers.len() {
+            let moved = self.layers.remove(layer);
+            self.layers.insert(to, moved);
+        }
+    }
+
+    // a copy of the layer right after it
+    pub fn duplicate_layer(&mut self, layer: usize) {
+        if let Some(original) = self.layers.get(layer) {
+            let copy = Layer {
+                name: format!("{} copy", original.name),
+                ..original.clone()
+            };
+            self.layers.insert(layer + 1, copy);
+        }
+    }
+
+    // the arrows of the layer go to the end of the other one, and the layer is removed
+    pub fn merge_layer(&mut self, layer: usize, into: usize) {
+        if layer == into || layer >= self.layers.len() || into >= self.layers.len() {
+            return;
+        }
+        let merged = self.layers.remove(layer);
+        let into = if into > layer { into - 1 } else { into };
+        self.layers[into].arrows.extend(merged.arrows);
+    }
+
+    pub fn enabled_layers(&self) -> impl Iterator<Item = &Layer> {
+        self.layers.iter().filter(|layer| layer.enabled)
+    }
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_duplicates_and_merges_layers() {
+        let (a, b) = (CellId::new(), CellId::new());
+        let mut configuration = Configuration::new();
+        configuration.layers[0].name = "first".into();
+        configuration.layers[0]
+            .arrows
+            .push(Arrow::new(a.clone(), b.clone()));
+        configuration.duplicate_layer(0);
+        configuration.layers.push(Layer::new());
+        let names = |configuration: &Configuration| {
+            configuration
+                .layers
+                .iter()
+                .map(|layer| layer.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&configuration), ["first", "first copy", "Unnamed"]);
+
+        configuration.move_layer(2, 0);
+        assert_eq!(names(&configuration), ["Unnamed", "first", "first copy"]);
+        configuration.merge_layer(2, 0);
+        assert_eq!(names(&configuration), ["Unnamed", "first"]);
+        assert_eq!(configuration.layers[0].arrows.len(), 1);
+        assert_eq!(configuration.layers[1].arrows.len(), 1);
+        // merging into itself changes nothing
+        configuration.merge_layer(1, 1);
+        assert_eq!(configuration.layers.len(), 2);
+    }
+
+    #[test]
+    fn seeded_arrows_are_the_same() {
+        let background = Color::rgb(15, 16, 23);
+        let (a, b) = (CellId::new(), CellId::new());
+        let arrows = || {
+            let mut configuration = Configuration::new();
+            configuration.seed = Some(7);
+            configuration.layers[0].enabled = true;
+            for _ in 0..3 {
+                configuration.add_arrow(a.clone(), b.clone(), background);
+            }
+            configuration.layers.remove(0).arrows
+        };
+        let (first, second) = (arrows(), arrows());
+        for (x, y) in first.iter().zip(&second) {
+            assert_eq!((x.color, x.padding), (y.color, y.padding));
+        }
+        // every arrow gets another color and padding
+        assert_ne!(first[0].color, first[1].color);
+        assert_ne!(first[0].padding, first[1].padding);
+    }
+
+    #[test]
+    fn seeds_dont_overlap() {
+        let (a, b) = (CellId::new(), CellId::new());
+        let paddings = |seed| {
+            let mut configuration = Configuration::new();
+            configuration.seed = Some(seed);
+            configuration.layers[0].enabled = true;
+            for _ in 0..3 {
+                configuration.add_arrow(a.clone(), b.clone(), Color::rgb(0, 0, 0));
+            }
+            let arrows = configuration.layers.remove(0).arrows;
+            arrows.iter().map(|arrow| arrow.padding).collect::<Vec<_>>()
+        };
+        // the next seed doesn't give the same arrows one later
+        let (first, next) = (paddings(7), paddings(8));
+        assert_ne!(first[1..], next[..2]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+
+// the lowest contrast ratio with the background a color of an arrow needs to be readable,
+// the one WCAG asks of graphics
+pub const MIN_CONTRAST: f64 = 3.0;
+
+// the colors a layer gives its new arrows, in order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Palette {
+    // for dark backgrounds
+    #[default]
+    Bright,
+    Pastel,
+    // for light backgrounds, e.g. printed exports
+    Dark,
+}
+
+impl Palette {
+    pub const ALL: [Self; 3] = [Self::Bright, Self::Pastel, Self::Dark];
+
+    pub fn colors(self) -> &'static [Color] {
+        match self {
+            Self::Bright => &BRIGHT,
+            Self::Pastel => &PASTEL,
+            Self::Dark => &DARK,
+        }
+    }
+
+    // the colors that can be read on the background, or the one closest to it when none can
+    pub fn readable(self, background: Color) -> Vec<Color> {
+        let colors = self.colors();
+        let readable = colors
+            .iter()
+            .copied()
+            .filter(|color| color.contrast(background) >= MIN_CONTRAST)
+            .collect::<Vec<_>>();
+        if !readable.is_empty() {
+            return readable;
+        }
+        colors
+            .iter()
+            .copied()
+            .max_by(|a, b| a.contrast(background).total_cmp(&b.contrast(background)))
+            .into_iter()
+            .collect()
+    }
+
+    // the readable color the fewest of the `used` ones have, the earlier one on a tie, so
+    // the arrows of a layer go through the whole palette before any color repeats
+    pub fn pick(self, used: &[Color], background: Color) -> Color {
+        self.readable(background)
+            .into_iter()
+            .min_by_key(|color| used.iter().filter(|used| *used == color).count())
+            .unwrap_or(self.colors()[0])
+    }
+
+    // the readable color after `color`, the first one when it's not in the palette
+    pub fn next(self, color: Color, background: Color) -> Color {
+        let readable = self.readable(background);
+        let next = readable
+            .iter()
+            .position(|c| *c == color)
+            .map_or(0, |i| (i + 1) % readable.len());
+        readable.get(next).copied().unwrap_or(self.colors()[0])
+    }
+}
+
+const BRIGHT: [Color; 9] = [
+    Color::rgb(0x4d, 0xab, 0xf7),
+    Color::rgb(0xff, 0x6b, 0x6b),
+    Color::rgb(0x69, 0xdb, 0x7c),
+    Color::rgb(0xff, 0xa9, 0x4d),
+    Color::rgb(0xda, 0x77, 0xf2),
+    Color::rgb(0xff, 0xd4, 0x3b),
+    Color::rgb(0x38, 0xd9, 0xa9),
+    Color::rgb(0xf7, 0x83, 0xac),
+    Color::rgb(0x74, 0x8f, 0xfc),
+];
+
+const PASTEL: [Color; 8] = [
+    Color::rgb(0xa5, 0xd8, 0xff),
+    Color::rgb(0xff, 0xc9, 0xc9),
+    Color::rgb(0xb2, 0xf2, 0xbb),
+    Color::rgb(0xff, 0xd8, 0xa8),
+    Color::rgb(0xee, 0xbe, 0xfa),
+    Color::rgb(0xff, 0xec, 0x99),
+    Color::rgb(0x96, 0xf2, 0xd7),
+    Color::rgb(0xba, 0xc8, 0xff),
+];
+
+const DARK: [Color; 8] = [
+    Color::rgb(0x18, 0x64, 0xab),
+    Color::rgb(0xc9, 0x2a, 0x2a),
+    Color::rgb(0x2b, 0x8a, 0x3e),
+    Color::rgb(0xd9, 0x48, 0x0f),
+    Color::rgb(0x86, 0x2e, 0x9c),
+    Color::rgb(0x08, 0x7f, 0x5b),
+    Color::rgb(0x36, 0x4f, 0xc7),
+    Color::rgb(0xa6, 0x1e, 0x4d),
+];
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Bright => "bright",
+            Self::Pastel => "pastel",
+            Self::Dark => "dark",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_readable_colors_in_turn() {
+        let dark = Color::rgb(15, 16, 23);
+        let light = Color::rgb(255, 255, 255);
+        for palette in Palette::ALL {
+            for background in [dark, light] {
+                let readable = palette.readable(background);
+                assert!(!readable.is_empty());
+                assert!(readable
+                    .iter()
+                    .all(|color| palette.colors().contains(color)));
+            }
+        }
+        // the pastel colors are all too light for a white background
+        assert_eq!(Palette::Pastel.readable(light).len(), 1);
+        assert!(Palette::Dark
+            .readable(light)
+            .iter()
+            .all(|color| color.contrast(light) >= MIN_CONTRAST));
+
+        let mut used = vec![];
+        for _ in 0..Palette::Bright.colors().len() {
+            used.push(Palette::Bright.pick(&used, dark));
+        }
+        assert_eq!(used, Palette::Bright.readable(dark));
+        assert_eq!(Palette::Bright.pick(&used, dark), used[0]);
+        assert_eq!(Palette::Bright.next(used[0], dark), used[1]);
+        assert_eq!(Palette::Bright.next(Color::rgb(0, 0, 0), dark), used[0]);
+    }
+}
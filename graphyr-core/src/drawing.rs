@@ -0,0 +1,110 @@
+use kurbo::{BezPath, Point, Rect, Size};
+
+use crate::{
+    color::Color,
+    configuration::{arrow::Arrow, Configuration},
+    routing::{arrow_shapes, label_rect, stub_line, trunk_line, HeadShape, Route, Router},
+};
+
+// the shapes of the arrows of enabled layers, the application and the exports compute
+// them here and only differ in how they draw them
+pub struct Drawing<'a> {
+    // drawn first, under the arrows
+    pub stubs: Vec<Stub>,
+    // in the order of the layers, the later ones are drawn over the earlier ones
+    pub arrows: Vec<DrawnArrow<'a>>,
+}
+
+// a dashed line from a cell to the closest border of the picture with a dot at its end,
+// it stands for an arrow whose other end is not in the picture
+pub struct Stub {
+    pub start: Point,
+    pub end: Point,
+    pub color: Color,
+}
+
+impl Stub {
+    pub const WIDTH: f64 = 2.0;
+    pub const DASHES: [f64; 2] = [6.0, 4.0];
+    pub const DOT_RADIUS: f64 = 4.0;
+}
+
+// an arrow with both ends in the picture
+pub struct DrawnArrow<'a> {
+    pub layer: usize,
+    pub index: usize,
+    pub arrow: &'a Arrow,
+    pub route: Route,
+    // the trunk of its bundle and its width, only the first arrow of a bundle has it
+    pub trunk: Option<(BezPath, f64)>,
+    // stroked with the width and the dashes of the arrow, the heads are drawn over it
+    pub line: BezPath,
+    pub heads: Vec<HeadShape>,
+    // the center of the "!" of an arrow that goes through cells
+    pub mark: Option<Point>,
+    // the box of the label, None without one
+    pub label: Option<Rect>,
+}
+
+// arrows with an end outside of `bounds` become stubs, the others are routed together,
+// `label_size` measures the box of a label with the font of the backend
+pub fn draw<'a>(
+    configuration: &'a Configuration,
+    router: &mut Router,
+    bounds: Rect,
+    label_size: impl Fn(&str) -> Size,
+) -> Drawing<'a> {
+    let mut stubs = vec![];
+    let mut routed = vec![];
+    let layers = configuration.layers.iter().enumerate();
+    for (layer_index, layer) in layers.filter(|(_, layer)| layer.enabled) {
+        let bundle = layer.bundled.then_some(layer_index);
+        for (index, arrow) in layer.arrows.iter().enumerate() {
+            let positions = router.positions();
+            match (positions.get(&arrow.from), positions.get(&arrow.to)) {
+                (Some(_), Some(_)) => {
+                    routed.push((layer_index, index, arrow, layer.routing_of(arrow), bundle));
+                }
+                (Some(rect), None) | (None, Some(rect)) => {
+                    let (start, end) = stub_line(rect, &bounds);
+                    stubs.push(Stub {
+                        start,
+                        end,
+                        color: arrow.color,
+                    });
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    let arrows = routed
+        .iter()
+        .map(|(_, _, arrow, routing, bundle)| (*arrow, *routing, *bundle))
+        .collect::<Vec<_>>();
+    let routes = router.route_all(&arrows);
+    let arrows = routed
+        .into_iter()
+        .zip(routes)
+        .filter_map(|((layer, index, arrow, routing, _), route)| {
+            let route = route?;
+            let positions = router.positions();
+            let (line, heads) = arrow_shapes(&route, arrow, routing, positions);
+            let label = (!arrow.label.is_empty())
+                .then(|| label_rect(&route.points, label_size(&arrow.label), positions))
+                .flatten();
+            Some(DrawnArrow {
+                layer,
+                index,
+                arrow,
+                trunk: trunk_line(&route, arrow, routing),
+                line,
+                heads,
+                mark: route.unroutable.then(|| route.middle()),
+                label,
+                route,
+            })
+        })
+        .collect();
+    Drawing { stubs, arrows }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use kurbo::{Rect, Size};
+
+use crate::cell::{Cell, CellId};
+
+// these mirror the styles used by the cell and table views of the application
+const PADDING: f64 = 5.;
+const GAP: f64 = 5.;
+const PANE_SIZE: f64 = 20.;
+const TITLE_HEIGHT: f64 = 20.;
+pub const FONT_SIZE: f64 = 12.;
+pub const LABEL_FONT_SIZE: f64 = 11.;
+// the smallest space a cell without a table takes
+const MIN_CELL_SIZE: Size = Size::new(60., 30.);
+
+pub struct CellLayout {
+    pub id: CellId,
+    pub title: String,
+    // the whole cell, including its table
+    pub rect: Rect,
+    // the title text input, arrows are attached to it
+    pub title_rect: Rect,
+}
+
+// positions of everything in a cell hierarchy, computed without any window,
+// it places the cells the same way the flex layout of the views does
+pub struct Layout {
+    pub size: Size,
+    pub cells: Vec<CellLayout>,
+    pub panes: Vec<Rect>,
+}
+
+impl Layout {
+    pub fn compute(cell: &Cell, show_panes: bool) -> Self {
+        let size = natural_size(cell, show_panes);
+        let mut layout = Self {
+            size,
+            cells: vec![],
+            panes: vec![],
+        };
+        layout.place_cell(cell, size.to_rect(), show_panes);
+        layout
+    }
+
+    // rects of the titles, the same thing Main collects for arrow routing
+    pub fn positions(&self) -> HashMap<CellId, Rect> {
+        self.cells
+            .iter()
+            .map(|cell| (cell.id.clone(), cell.title_rect))
+            .collect()
+    }
+
+    // rects of the whole cells, arrows to the cells inside of them end at their border
+    pub fn boxes(&self) -> HashMap<CellId, Rect> {
+        self.cells
+            .iter()
+            .map(|cell| (cell.id.clone(), cell.rect))
+            .collect()
+    }
+
+    fn place_cell(&mut self, cell: &Cell, rect: Rect, show_panes: bool) {
+        let inner = rect.inset(-PADDING);
+        let title_width = title_width(&cell.title).min(inner.width());
+        let table = cell.table.as_ref();
+
+        let title_top = if table.is_some() {
+            inner.y0
+        } else {
+            inner.center().y - TITLE_HEIGHT / 2.
+        };
+        let title_rect = Rect::new(
+            inner.center().x - title_width / 2.,
+            title_top,
+            inner.center().x + title_width / 2.,
+            title_top + TITLE_HEIGHT,
+        );
+        self.cells.push(CellLayout {
+            id: cell.id.clone(),
+            title: cell.title.clone(),
+            rect,
+            title_rect,
+        });
+
+        if let Some(table) = table {
+            let table_rect = Rect::new(
+                inner.x0,
+                title_rect.y1 + GAP,
+                inner.x1,
+                inner.y1.max(title_rect.y1 + GAP),
+            );
+            let cells = &table.cells;
+            let (row_count, col_count) = (cells.rows(), cells.cols());
+
+            let cells_rect = if show_panes {
+                Rect::new(
+                    table_rect.x0 + PANE_SIZE,
+                    table_rect.y0 + PANE_SIZE,
+                    table_rect.x1,
+                    table_rect.y1,
+                )
+            } else {
+                table_rect
+            };
+            let cell_width = cells_rect.width() / col_count as f64;
+            let cell_height = cells_rect.height() / row_count as f64;
+
+            if show_panes {
+                for col in 0..col_count {
+                    let x0 = cells_rect.x0 + col as f64 * cell_width;
+                    self.panes
+                        .push(Rect::new(x0, table_rect.y0, x0 + cell_width, cells_rect.y0));
+                }
+                for row in 0..row_count {
+                    let y0 = cells_rect.y0 + row as f64 * cell_height;
+                    self.panes.push(Rect::new(
+                        table_rect.x0,
+                        y0,
+                        cells_rect.x0,
+                        y0 + cell_height,
+                    ));
+                }
+            }
+
+            for (row_id, row) in cells.data.iter().enumerate() {
+                for (col_id, cell) in row.iter().enumerate() {
+                    let x0 = cells_rect.x0 + col_id as f64 * cell_width;
+                    let y0 = cells_rect.y0 + row_id as f64 * cell_height;
+                    let rect = Rect::new(x0, y0, x0 + cell_width, y0 + cell_height);
+                    self.place_cell(cell, rect, show_panes);
+                }
+            }
+        }
+    }
+}
+
+// an estimation, we don't have the font metrics without a window
+fn title_width(title: &str) -> f64 {
+    text_width(title, FONT_SIZE) + 12.
+}
+
+// the box of an arrow label, estimated the same way as the titles
+pub fn label_size(label: &str) -> Size {
+    Size::new(
+        text_width(label, LABEL_FONT_SIZE) + 8.,
+        LABEL_FONT_SIZE + 6.,
+    )
+}
+
+fn text_width(text: &str, font_size: f64) -> f64 {
+    text.chars().count() as f64 * font_size * 0.6
+}
+
+// the size in which the whole hierarchy fits, cells of a table share the space equally,
+// so every one of them gets the space of the biggest one
+fn natural_size(cell: &Cell, show_panes: bool) -> Size {
+    let title_width = title_width(&cell.title);
+    match &cell.table {
+        None => Size::new(
+            MIN_CELL_SIZE.width.max(title_width + 2. * PADDING),
+            MIN_CELL_SIZE.height,
+        ),
+        Some(table) => {
+            let cells = &table.cells;
+            let biggest = cells
+                .data
+                .iter()
+                .flatten()
+                .map(|cell| natural_size(cell, show_panes))
+                .fold(Size::ZERO, |biggest, size| {
+                    Size::new(
+                        biggest.width.max(size.width),
+                        biggest.height.max(size.height),
+                    )
+                });
+            let panes = if show_panes { PANE_SIZE } else { 0. };
+            let table_size = Size::new(
+                biggest.width * cells.cols() as f64 + panes,
+                biggest.height * cells.rows() as f64 + panes,
+            );
+            Size::new(
+                title_width.max(table_size.width) + 2. * PADDING,
+                TITLE_HEIGHT + GAP + table_size.height + 2. * PADDING,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(cols: usize, titles: &[&str]) -> Cell {
+        let mut cell = Cell::new(None, 0);
+        cell.create_table();
+        let cells = &mut cell.table.as_mut().unwrap().cells;
+        for col in 1..cols {
+            cells.add_col(col);
+        }
+        for (cell, title) in cells.data[0].iter_mut().zip(titles) {
+            cell.title = title.to_string();
+        }
+        cell
+    }
+
+    #[test]
+    fn places_cells_side_by_side() {
+        let cell = table(2, &["a", "a much longer title"]);
+        let layout = Layout::compute(&cell, true);
+        assert_eq!(layout.cells.len(), 3);
+        // a pane over each column and one next to the row
+        assert_eq!(layout.panes.len(), 3);
+
+        let bounds = layout.size.to_rect();
+        let [root, a, b] = [0, 1, 2].map(|i| &layout.cells[i]);
+        assert_eq!(root.rect, bounds);
+        assert!(a.rect.x1 <= b.rect.x0);
+        // the cells share the space equally, so both fit the longer title
+        assert_eq!(a.rect.size(), b.rect.size());
+        assert!(b.title_rect.width() > a.title_rect.width());
+        for cell in [a, b] {
+            assert!(bounds.contains_rect(cell.rect));
+            assert!(cell.rect.contains_rect(cell.title_rect));
+            assert!(cell.title_rect.y0 > root.title_rect.y1);
+        }
+        assert_eq!(layout.positions()[&a.id], a.title_rect);
+        assert_eq!(layout.boxes()[&b.id], b.rect);
+    }
+
+    #[test]
+    fn panes_take_space() {
+        let cell = table(2, &[]);
+        let with = Layout::compute(&cell, true).size;
+        let without = Layout::compute(&cell, false).size;
+        assert_eq!(with.width - without.width, PANE_SIZE);
+        assert_eq!(with.height - without.height, PANE_SIZE);
+    }
+
+    #[test]
+    fn labels_grow_with_text() {
+        assert!(label_size("a longer label").width > label_size("short").width);
+        assert_eq!(label_size("a").height, label_size("abc").height);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::color::Color;
+
+pub mod layout;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod svg;
+
+// colors and font of an exported picture, the defaults match the dark theme of the application
+#[derive(Clone, Debug)]
+pub struct ExportTheme {
+    pub background: Color,
+    pub secondary_background: Color,
+    pub foreground: Color,
+    pub border: Color,
+    // background of the marks of arrows that go through cells
+    pub error: Color,
+    // the font used for titles, the system fonts are used when it's None
+    pub font: Option<&'static [u8]>,
+}
+
+impl Default for ExportTheme {
+    fn default() -> Self {
+        Self {
+            background: Color::rgb(15, 16, 23),
+            secondary_background: Color::rgb(32, 35, 51),
+            foreground: Color::rgb(255, 255, 255),
+            border: Color::rgb(255, 255, 255),
+            error: Color::rgb(92, 26, 34),
+            font: None,
+        }
+    }
+}
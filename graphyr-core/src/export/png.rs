@@ -0,0 +1,94 @@
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use resvg::{tiny_skia, usvg};
+
+use super::{svg, ExportTheme};
+use crate::{cell::CellPos, Data};
+
+pub fn save(
+    data: &Data,
+    pos: Option<&CellPos>,
+    theme: &ExportTheme,
+    scale: f32,
+    path: &Path,
+) -> anyhow::Result<()> {
+    fs::write(path, render(data, pos, theme, scale)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+// rasterizes the same picture as the SVG export on the CPU, without the highest cell
+// when `pos` is given, `scale` multiplies the resolution
+pub fn render(
+    data: &Data,
+    pos: Option<&CellPos>,
+    theme: &ExportTheme,
+    scale: f32,
+) -> anyhow::Result<Vec<u8>> {
+    let pos = pos.cloned().unwrap_or_else(CellPos::new);
+    let svg = svg::render(data, &pos, theme);
+
+    let mut options = usvg::Options::default();
+    match theme.font {
+        Some(font) => {
+            options.fontdb_mut().load_font_data(font.to_vec());
+            // the titles ask for a sans-serif font, so the given one has to be picked for it
+            let family = options
+                .fontdb
+                .faces()
+                .last()
+                .and_then(|face| face.families.first())
+                .map(|(family, _)| family.clone());
+            if let Some(family) = family {
+                options.fontdb_mut().set_sans_serif_family(family);
+            }
+        }
+        None => options.fontdb_mut().load_system_fonts(),
+    }
+    let tree = usvg::Tree::from_str(&svg, &options).context("failed to parse the SVG")?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .context("invalid scale")?;
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).context("the image is too big")?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap.encode_png().context("failed to encode the PNG")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{configuration::arrow::Arrow, export::layout::Layout};
+
+    #[test]
+    fn renders_at_scale() {
+        let mut data = Data::new();
+        data.cell.create_table();
+        let cells = &mut data.cell.table.as_mut().unwrap().cells;
+        cells.add_col(1);
+        let (a, b) = (cells.data[0][0].id.clone(), cells.data[0][1].id.clone());
+        data.configuration.layers[0].enabled = true;
+        data.configuration.layers[0].arrows.push(Arrow::new(a, b));
+
+        let theme = ExportTheme::default();
+        let png = render(&data, None, &theme, 2.).unwrap();
+        let pixmap = tiny_skia::Pixmap::decode_png(&png).unwrap();
+        let size = Layout::compute(&data.cell, true).size;
+        assert_eq!(pixmap.width(), 2 * size.width.ceil() as u32);
+        assert_eq!(pixmap.height(), 2 * size.height.ceil() as u32);
+        // inside of the border of the highest cell
+        let corner = pixmap.pixel(4, 4).unwrap();
+        let background = theme.background;
+        assert_eq!(
+            [corner.red(), corner.green(), corner.blue(), corner.alpha()],
+            [background.r, background.g, background.b, 255]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::{fmt::Write as _, fs, path::Path};
+
+use anyhow::Context;
+use kurbo::{BezPath, Point, Rect};
+
+use super::{
+    layout::{label_size, Layout, FONT_SIZE, LABEL_FONT_SIZE},
+    ExportTheme,
+};
+use crate::{
+    cell::CellPos,
+    color::Color,
+    configuration::arrow::Arrow,
+    drawing::{self, Stub},
+    routing::{HeadShape, Route, Router},
+    Data,
+};
+
+pub fn save(data: &Data, pos: &CellPos, theme: &ExportTheme, path: &Path) -> anyhow::Result<()> {
+    fs::write(path, render(data, pos, theme))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+// renders the cell at `pos` with everything inside of it and the arrows of enabled layers
+pub fn render(data: &Data, pos: &CellPos, theme: &ExportTheme) -> String {
+    let (cell, _) = data.get_cell_or_highest(pos);
+    let configuration = &data.configuration;
+    let layout = Layout::compute(cell, configuration.show_panes);
+    let positions = layout.positions();
+    let bounds = layout.size.to_rect();
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = layout.size.width,
+        h = layout.size.height,
+    )
+    .unwrap();
+    write_rect(&mut svg, bounds, Some(theme.background), None);
+
+    for pane in &layout.panes {
+        write_rect(
+            &mut svg,
+            *pane,
+            Some(theme.secondary_background),
+            Some(theme.border),
+        );
+    }
+
+    for cell in &layout.cells {
+        if configuration.show_border {
+            write_rect(&mut svg, cell.rect, None, Some(theme.border));
+        }
+        if !cell.title.is_empty() {
+            write_text(
+                &mut svg,
+                cell.title_rect.center(),
+                FONT_SIZE,
+                theme.foreground,
+                &cell.title,
+            );
+        }
+    }
+
+    let mut router = Router::new(positions, layout.boxes());
+    let drawing = drawing::draw(configuration, &mut router, bounds, label_size);
+    for stub in &drawing.stubs {
+        write_stub(&mut svg, stub);
+    }
+    for drawn in &drawing.arrows {
+        let arrow = drawn.arrow;
+        if let Some((trunk, width)) = &drawn.trunk {
+            write_line(&mut svg, trunk, arrow, *width);
+        }
+        write_line(&mut svg, &drawn.line, arrow, arrow.width);
+        for head in &drawn.heads {
+            write_head(&mut svg, head, arrow);
+        }
+        if let Some(center) = drawn.mark {
+            write_unroutable_mark(&mut svg, center, arrow.color, theme);
+        }
+        if let Some(rect) = drawn.label {
+            write_rect(&mut svg, rect, Some(theme.background), Some(arrow.color));
+            write_text(
+                &mut svg,
+                rect.center(),
+                LABEL_FONT_SIZE,
+                arrow.color,
+                &arrow.label,
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn write_rect(svg: &mut String, rect: Rect, fill: Option<Color>, stroke: Option<Color>) {
+    writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="{}" stroke-width="1"/>"#,
+        rect.x0,
+        rect.y0,
+        rect.width(),
+        rect.height(),
+        fill.map(hex).unwrap_or_else(|| "none".into()),
+        stroke.map(hex).unwrap_or_else(|| "none".into()),
+    )
+    .unwrap();
+}
+
+fn write_text(svg: &mut String, center: Point, font_size: f64, color: Color, text: &str) {
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-family="Inter, sans-serif" font-size="{font_size}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+        center.x,
+        center.y,
+        hex(color),
+        escape(text),
+    )
+    .unwrap();
+}
+
+fn write_stub(svg: &mut String, stub: &Stub) {
+    let [dash, gap] = Stub::DASHES;
+    writeln!(
+        svg,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-dasharray="{dash} {gap}"/>"#,
+        stub.start.x,
+        stub.start.y,
+        stub.end.x,
+        stub.end.y,
+        hex(stub.color),
+        Stub::WIDTH,
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+        stub.end.x,
+        stub.end.y,
+        Stub::DOT_RADIUS,
+        hex(stub.color),
+    )
+    .unwrap();
+}
+
+fn write_line(svg: &mut String, path: &BezPath, arrow: &Arrow, width: f64) {
+    let dashes = arrow.style.dashes(width);
+    let dasharray = if dashes.is_empty() {
+        String::new()
+    } else {
+        let dashes = dashes
+            .iter()
+            .map(|dash| dash.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(r#" stroke-dasharray="{dashes}""#)
+    };
+    writeln!(
+        svg,
+        r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"{dasharray}/>"#,
+        path.to_svg(),
+        hex(arrow.color),
+        width,
+    )
+    .unwrap();
+}
+
+// a "!" in a circle, it marks an arrow that goes through cells
+fn write_unroutable_mark(svg: &mut String, center: Point, color: Color, theme: &ExportTheme) {
+    writeln!(
+        svg,
+        r#"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="{}" stroke-width="1"/>"#,
+        center.x,
+        center.y,
+        Route::MARK_RADIUS,
+        hex(theme.error),
+        hex(color),
+    )
+    .unwrap();
+    write_text(svg, center, LABEL_FONT_SIZE, theme.foreground, "!");
+}
+
+fn write_head(svg: &mut String, head: &HeadShape, arrow: &Arrow) {
+    let color = hex(arrow.color);
+    if head.filled {
+        writeln!(svg, r#"<path d="{}" fill="{color}"/>"#, head.path.to_svg()).unwrap();
+    } else {
+        writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{color}" stroke-width="{}" stroke-linejoin="round"/>"#,
+            head.path.to_svg(),
+            arrow.width,
+        )
+        .unwrap();
+    }
+}
+
+fn hex(color: Color) -> String {
+    color.to_hex()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a highest cell with a 1x2 table and a labeled arrow between the two inner cells
+    fn document() -> Data {
+        let mut data = Data::new();
+        data.cell.title = "root".into();
+        data.cell.create_table();
+        let cells = &mut data.cell.table.as_mut().unwrap().cells;
+        cells.add_col(1);
+        cells.data[0][0].title = "a".into();
+        cells.data[0][1].title = "b".into();
+        let (a, b) = (cells.data[0][0].id.clone(), cells.data[0][1].id.clone());
+        let layer = &mut data.configuration.layers[0];
+        layer.enabled = true;
+        layer.arrows.push(Arrow {
+            color: Color::rgb(255, 0, 128),
+            label: "uses".into(),
+            ..Arrow::new(a, b)
+        });
+        data
+    }
+
+    fn count(svg: &str, element: &str) -> usize {
+        svg.matches(&format!("<{element} ")).count()
+    }
+
+    #[test]
+    fn renders_cells_and_arrows() {
+        let svg = render(&document(), &CellPos::new(), &ExportTheme::default());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // the background, 3 panes, 3 borders and the box of the label
+        assert_eq!(count(&svg, "rect"), 8);
+        // the line and its head
+        assert_eq!(count(&svg, "path"), 2);
+        assert!(svg.contains(r##"stroke="#ff0080""##));
+        for text in ["root", "a", "b", "uses"] {
+            assert!(svg.contains(&format!(">{text}</text>")));
+        }
+        assert!(!svg.contains("<line "));
+    }
+
+    #[test]
+    fn leaves_out_hidden_parts() {
+        let mut data = document();
+        data.configuration.show_border = false;
+        data.configuration.show_panes = false;
+        data.configuration.layers[0].enabled = false;
+        let svg = render(&data, &CellPos::new(), &ExportTheme::default());
+        assert_eq!(count(&svg, "rect"), 1);
+        assert_eq!(count(&svg, "path"), 0);
+        assert_eq!(count(&svg, "text"), 3);
+    }
+
+    #[test]
+    fn draws_stub_for_end_outside() {
+        let svg = render(
+            &document(),
+            &CellPos::new().child(0, 0),
+            &ExportTheme::default(),
+        );
+        assert!(svg.contains(r##"<line "##));
+        assert!(svg.contains(r##"stroke="#ff0080" stroke-width="2" stroke-dasharray="6 4""##));
+        assert!(svg.contains(r##"r="4" fill="#ff0080""##));
+        assert_eq!(count(&svg, "path"), 0);
+        assert!(!svg.contains(">b</text>"));
+    }
+
+    #[test]
+    fn escapes_text() {
+        let mut data = document();
+        data.cell.title = "<a & b>".into();
+        let svg = render(&data, &CellPos::new(), &ExportTheme::default());
+        assert!(svg.contains(">&lt;a &amp; b&gt;</text>"));
+    }
+}
@@ -0,0 +1,288 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::Color,
+    configuration::{arrow::Arrow, layer::Layer, Configuration},
+    Data,
+};
+
+// version of the documents we write, bump it together with adding a migration
+pub const CURRENT_VERSION: u32 = 3;
+
+// the header that is written around the data
+#[derive(Serialize)]
+struct Document<'a> {
+    version: u32,
+    data: &'a Data,
+}
+
+// the same header for reading documents of the current version
+#[derive(Deserialize)]
+struct StoredDocument {
+    data: Data,
+}
+
+// only the version of a document, documents without one were written before versioning
+// existed, they are version 1
+#[derive(Deserialize)]
+struct Version {
+    #[serde(default = "first_version")]
+    version: u32,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+pub fn save(data: &Data) -> anyhow::Result<String> {
+    let document = Document {
+        version: CURRENT_VERSION,
+        data,
+    };
+    ron::to_string(&document).context("failed to serialize the document")
+}
+
+pub fn save_file(data: &Data, path: &Path) -> anyhow::Result<()> {
+    fs::write(path, save(data)?).with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub fn load_bytes(bytes: &[u8]) -> anyhow::Result<Data> {
+    let text = std::str::from_utf8(bytes).context("the document is not valid UTF-8")?;
+    load(text)
+}
+
+// older documents are read into the types of their version and migrated from there, every
+// migration turns the types of one version into the ones of the next, so nothing goes
+// through a generic value, which would forget the names of enum variants without fields
+pub fn load(text: &str) -> anyhow::Result<Data> {
+    let version = parse::<Version>(text)?.version;
+    let data = match version {
+        0 => bail!("invalid document version 0"),
+        // v1 was the bare data of v2, v2 wraps it in a header
+        1 => parse::<v2::Data>(text).map(v2_to_v3),
+        2 => parse::<v2::Document>(text).map(|document| v2_to_v3(document.data)),
+        CURRENT_VERSION => parse::<StoredDocument>(text).map(|document| document.data),
+        _ => bail!(
+            "the document has version {version}, but the newest supported one is {CURRENT_VERSION}"
+        ),
+    };
+    data.context("the document doesn't match the expected structure")
+}
+
+fn parse<'a, T: Deserialize<'a>>(text: &'a str) -> anyhow::Result<T> {
+    ron::from_str(text).map_err(position_error)
+}
+
+fn position_error(error: ron::error::SpannedError) -> anyhow::Error {
+    anyhow!(
+        "line {}, column {}: {}",
+        error.position.line,
+        error.position.col,
+        error.code
+    )
+}
+
+// the documents of versions 1 and 2, only the colors of arrows were stored differently.
+// Fields that were added later with a default are read here too, a document keeps them
+// whatever its version is. When the format changes again, the current types are copied
+// into a module like this one for the version that is left behind.
+mod v2 {
+    use serde::Deserialize;
+
+    use crate::{
+        cell::{Cell, CellId},
+        configuration::{
+            arrow::{self, Anchor, Head, LineStyle, Routing, Waypoint},
+            palette::Palette,
+        },
+    };
+
+    #[derive(Deserialize)]
+    pub struct Document {
+        pub data: Data,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Data {
+        pub cell: Cell,
+        pub configuration: Configuration,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Configuration {
+        pub show_border: bool,
+        pub show_panes: bool,
+        pub layers: Vec<Layer>,
+        #[serde(default)]
+        pub seed: Option<u64>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Layer {
+        pub name: String,
+        pub enabled: bool,
+        pub arrows: Vec<Arrow>,
+        #[serde(default)]
+        pub routing: Routing,
+        #[serde(default)]
+        pub bundled: bool,
+        #[serde(default)]
+        pub palette: Palette,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Arrow {
+        pub from: CellId,
+        pub to: CellId,
+        pub color: Color,
+        pub padding: f64,
+        #[serde(default)]
+        pub label: String,
+        #[serde(default)]
+        pub style: LineStyle,
+        #[serde(default = "arrow::default_width")]
+        pub width: f64,
+        #[serde(default)]
+        pub start: Head,
+        #[serde(default = "arrow::default_end")]
+        pub end: Head,
+        #[serde(default)]
+        pub routing: Option<Routing>,
+        #[serde(default)]
+        pub from_anchor: Anchor,
+        #[serde(default)]
+        pub to_anchor: Anchor,
+        #[serde(default)]
+        pub waypoints: Vec<Waypoint>,
+    }
+
+    // a color of the UI library, with floating point components
+    #[derive(Deserialize)]
+    pub struct Color {
+        pub components: [f64; 4],
+    }
+}
+
+// v3 stores plain 8 bit channels instead of the floating point components of a UI color
+fn v2_to_v3(data: v2::Data) -> Data {
+    let configuration = data.configuration;
+    let layers = configuration.layers.into_iter().map(|layer| Layer {
+        name: layer.name,
+        enabled: layer.enabled,
+        arrows: layer.arrows.into_iter().map(arrow_to_v3).collect(),
+        routing: layer.routing,
+        bundled: layer.bundled,
+        palette: layer.palette,
+    });
+    Data {
+        cell: data.cell,
+        configuration: Configuration {
+            show_border: configuration.show_border,
+            show_panes: configuration.show_panes,
+            layers: layers.collect(),
+            seed: configuration.seed,
+        },
+    }
+}
+
+fn arrow_to_v3(arrow: v2::Arrow) -> Arrow {
+    let [r, g, b, a] = arrow.color.components.map(channel);
+    Arrow {
+        from: arrow.from,
+        to: arrow.to,
+        color: Color::rgba(r, g, b, a),
+        padding: arrow.padding,
+        label: arrow.label,
+        style: arrow.style,
+        width: arrow.width,
+        start: arrow.start,
+        end: arrow.end,
+        routing: arrow.routing,
+        from_anchor: arrow.from_anchor,
+        to_anchor: arrow.to_anchor,
+        waypoints: arrow.waypoints,
+    }
+}
+
+fn channel(component: f64) -> u8 {
+    (component.clamp(0., 1.) * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::arrow::{Head, LineStyle, Routing};
+
+    const V1_DOCUMENT: &str = r#"(cell:(title:"root",id:("0b8a5c6e-4f51-4bc1-9a53-3c2f3f0c7a11"),table:Some((cells:(data:[[(title:"inner",id:("5d2f8b1e-8f0c-4c44-8a3e-6b1f0e9d2c33"),table:None,hierarchy_depth:1)]],hierarchy_depth:1))),hierarchy_depth:0),configuration:(show_border:true,show_panes:false,layers:[(name:"Unnamed",enabled:true,arrows:[])]))"#;
+    const V2_DOCUMENT: &str = r#"(version:2,data:(cell:(title:"root",id:("0b8a5c6e-4f51-4bc1-9a53-3c2f3f0c7a11"),table:Some((cells:(data:[[(title:"a",id:("5d2f8b1e-8f0c-4c44-8a3e-6b1f0e9d2c33"),table:None,hierarchy_depth:1),(title:"b",id:("9c4e7a2d-1b3f-4e5a-8d6c-0f2e4a6b8c10"),table:None,hierarchy_depth:1)]],hierarchy_depth:1))),hierarchy_depth:0),configuration:(show_border:true,show_panes:true,layers:[(name:"Unnamed",enabled:true,arrows:[(from:("5d2f8b1e-8f0c-4c44-8a3e-6b1f0e9d2c33"),to:("9c4e7a2d-1b3f-4e5a-8d6c-0f2e4a6b8c10"),color:(components:(1.0,0.5,0.0,1.0),cs:()),padding:7.5)])])))"#;
+
+    #[test]
+    fn v1_has_no_header() {
+        let document: Version = ron::from_str(V1_DOCUMENT).unwrap();
+        assert_eq!(document.version, 1);
+    }
+
+    #[test]
+    fn v2_to_v3_converts_colors() {
+        let data = load(V2_DOCUMENT).unwrap();
+        let arrow = &data.configuration.layers[0].arrows[0];
+        assert_eq!(arrow.color, Color::rgba(255, 128, 0, 255));
+        assert_eq!(arrow.padding, 7.5);
+    }
+
+    #[test]
+    fn migration_keeps_variant_names() {
+        let document = V2_DOCUMENT.replace(
+            "padding:7.5",
+            "padding:7.5,style:Dashed,start:Diamond,end:Open,routing:Some(Curved)",
+        );
+        let data = load(&document).unwrap();
+        let arrow = &data.configuration.layers[0].arrows[0];
+        assert_eq!(arrow.style, LineStyle::Dashed);
+        assert_eq!(arrow.start, Head::Diamond);
+        assert_eq!(arrow.end, Head::Open);
+        assert_eq!(arrow.routing, Some(Routing::Curved));
+    }
+
+    #[test]
+    fn loads_v1() {
+        let data = load(V1_DOCUMENT).unwrap();
+        assert_eq!(data.cell.title, "root");
+        assert!(!data.configuration.show_panes);
+        let table = data.cell.table.unwrap();
+        assert_eq!(table.cells.get(0, 0).unwrap().title, "inner");
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = load(V2_DOCUMENT).unwrap();
+        let saved = save(&data).unwrap();
+        let document: Version = ron::from_str(&saved).unwrap();
+        assert_eq!(document.version, CURRENT_VERSION);
+        let loaded = load(&saved).unwrap();
+        assert_eq!(loaded.cell.id, data.cell.id);
+        assert_eq!(
+            loaded.configuration.layers[0].arrows[0].color,
+            data.configuration.layers[0].arrows[0].color
+        );
+        assert_eq!(
+            loaded.configuration.layers[0].arrows[0].end,
+            data.configuration.layers[0].arrows[0].end
+        );
+    }
+
+    #[test]
+    fn reports_error_position() {
+        let error = load(&V1_DOCUMENT[..20]).unwrap_err();
+        assert!(error.to_string().starts_with("line 1, column 21"));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let document = format!("(version:{},data:())", CURRENT_VERSION + 1);
+        assert!(load(&document).is_err());
+    }
+}
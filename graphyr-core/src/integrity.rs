@@ -0,0 +1,452 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    cell::{Cell, CellId, CellPos},
+    Data,
+};
+
+// something that is wrong with a document, these appear after edits that remove cells
+// (the arrows of removed cells are left behind) or in documents edited by hand
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    // the arrow at `arrow` in the layer at `layer` points to cells that don't exist
+    DanglingArrow {
+        layer: usize,
+        arrow: usize,
+        missing: Vec<CellId>,
+    },
+    // waypoints of the arrow are placed on cells that don't exist, so it doesn't go through
+    // them
+    DanglingWaypoints {
+        layer: usize,
+        arrow: usize,
+        count: usize,
+    },
+    // more cells share one id, arrows can't tell them apart
+    DuplicateId {
+        id: CellId,
+        positions: Vec<CellPos>,
+    },
+    // the rows of the table of the cell at `pos` don't have the same number of cells,
+    // or it has no cells at all, it can't be shown
+    MalformedTable {
+        pos: CellPos,
+        rows: Vec<usize>,
+    },
+    // the depth stored in a cell (or in its table when `table` is set)
+    // doesn't match its place in the hierarchy
+    WrongDepth {
+        pos: CellPos,
+        table: bool,
+        stored: usize,
+        expected: usize,
+    },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DanglingArrow {
+                layer,
+                arrow,
+                missing,
+            } => write!(
+                f,
+                "arrow {arrow} of layer {layer} points to {} missing cell{}",
+                missing.len(),
+                if missing.len() > 1 { "s" } else { "" }
+            ),
+            Self::DanglingWaypoints {
+                layer,
+                arrow,
+                count,
+            } => write!(
+                f,
+                "arrow {arrow} of layer {layer} has {count} waypoint{} on missing cells",
+                if *count > 1 { "s" } else { "" }
+            ),
+            Self::DuplicateId { id, positions } => write!(
+                f,
+                "{} cells share the id {id}: {}",
+                positions.len(),
+                positions.iter().map(place).collect::<Vec<_>>().join(", ")
+            ),
+            Self::MalformedTable { pos, rows } if rows.iter().all(|cells| *cells == 0) => {
+                write!(f, "the table of {} has no cells", place(pos))
+            }
+            Self::MalformedTable { pos, rows } => write!(
+                f,
+                "the table of {} has rows of {} cells",
+                place(pos),
+                rows.iter()
+                    .map(|cells| cells.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::WrongDepth {
+                pos,
+                table,
+                stored,
+                expected,
+            } => write!(
+                f,
+                "{}{} has depth {stored} instead of {expected}",
+                if *table { "the table of " } else { "" },
+                place(pos)
+            ),
+        }
+    }
+}
+
+// what to do with the ends of arrows that point to cells which don't exist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DanglingArrows {
+    Remove,
+    // points the missing ends to the given cell, arrows that would start and end
+    // in the same cell are removed
+    Reattach(CellId),
+}
+
+pub fn check(data: &Data) -> Vec<Problem> {
+    let mut problems = vec![];
+    let mut positions: HashMap<CellId, Vec<CellPos>> = HashMap::new();
+    check_cell(&data.cell, CellPos::new(), 0, &mut positions, &mut problems);
+
+    let mut duplicates = positions
+        .iter()
+        .filter(|(_, positions)| positions.len() > 1)
+        .map(|(id, positions)| Problem::DuplicateId {
+            id: id.clone(),
+            positions: positions.clone(),
+        })
+        .collect::<Vec<_>>();
+    // the map has no order, but the report should be stable
+    duplicates.sort_by_key(|problem| problem.to_string());
+    problems.extend(duplicates);
+
+    for (layer_id, layer) in data.configuration.layers.iter().enumerate() {
+        for (arrow_id, arrow) in layer.arrows.iter().enumerate() {
+            let missing = [&arrow.from, &arrow.to]
+                .into_iter()
+                .filter(|id| !positions.contains_key(id))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                problems.push(Problem::DanglingArrow {
+                    layer: layer_id,
+                    arrow: arrow_id,
+                    missing,
+                });
+            }
+            let count = arrow
+                .waypoints
+                .iter()
+                .filter(|waypoint| !positions.contains_key(&waypoint.cell))
+                .count();
+            if count > 0 {
+                problems.push(Problem::DanglingWaypoints {
+                    layer: layer_id,
+                    arrow: arrow_id,
+                    count,
+                });
+            }
+        }
+    }
+    problems
+}
+
+// fixes everything `check` reports, the first of the cells sharing an id keeps it
+// (so arrows stay attached to it) and the others get new ones, waypoints on missing cells
+// are removed and malformed tables are filled up with empty cells
+pub fn repair(data: &mut Data, dangling: &DanglingArrows) {
+    repair_tables(data);
+    repair_cell(&mut data.cell, 0, &mut HashSet::new());
+
+    let mut existing = HashSet::new();
+    data.cell.for_each_cell(&mut |cell| {
+        existing.insert(cell.id.clone());
+    });
+    for layer in &mut data.configuration.layers {
+        layer.arrows.retain_mut(|arrow| {
+            arrow
+                .waypoints
+                .retain(|waypoint| existing.contains(&waypoint.cell));
+            for end in [&mut arrow.from, &mut arrow.to] {
+                if existing.contains(end) {
+                    continue;
+                }
+                match dangling {
+                    DanglingArrows::Reattach(id) if existing.contains(id) => *end = id.clone(),
+                    _ => return false,
+                }
+            }
+            arrow.from != arrow.to
+        });
+    }
+}
+
+// only fills up the malformed tables, the other problems don't stop a document from being
+// shown or exported, returns whether there were any
+pub fn repair_tables(data: &mut Data) -> bool {
+    fill_tables(&mut data.cell)
+}
+
+fn fill_tables(cell: &mut Cell) -> bool {
+    let Some(table) = &mut cell.table else {
+        return false;
+    };
+    let mut filled = table.cells.fill();
+    for cell in table.cells.data.iter_mut().flatten() {
+        filled |= fill_tables(cell);
+    }
+    filled
+}
+
+fn check_cell(
+    cell: &Cell,
+    pos: CellPos,
+    depth: usize,
+    positions: &mut HashMap<CellId, Vec<CellPos>>,
+    problems: &mut Vec<Problem>,
+) {
+    positions
+        .entry(cell.id.clone())
+        .or_default()
+        .push(pos.clone());
+    if cell.hierarchy_depth != depth {
+        problems.push(Problem::WrongDepth {
+            pos: pos.clone(),
+            table: false,
+            stored: cell.hierarchy_depth,
+            expected: depth,
+        });
+    }
+
+    let Some(table) = &cell.table else {
+        return;
+    };
+    if table.cells.hierarchy_depth() != depth + 1 {
+        problems.push(Problem::WrongDepth {
+            pos: pos.clone(),
+            table: true,
+            stored: table.cells.hierarchy_depth(),
+            expected: depth + 1,
+        });
+    }
+    let rows = table.cells.data.iter().map(Vec::len).collect::<Vec<_>>();
+    if rows.iter().all(|cells| *cells == 0) || rows.iter().any(|cells| *cells != rows[0]) {
+        problems.push(Problem::MalformedTable {
+            pos: pos.clone(),
+            rows,
+        });
+    }
+    for (row_id, row) in table.cells.data.iter().enumerate() {
+        for (col_id, cell) in row.iter().enumerate() {
+            check_cell(
+                cell,
+                pos.child(row_id, col_id),
+                depth + 1,
+                positions,
+                problems,
+            );
+        }
+    }
+}
+
+fn repair_cell(cell: &mut Cell, depth: usize, seen: &mut HashSet<CellId>) {
+    if !seen.insert(cell.id.clone()) {
+        cell.id = CellId::new();
+        seen.insert(cell.id.clone());
+    }
+    cell.hierarchy_depth = depth;
+
+    if let Some(table) = &mut cell.table {
+        table.cells.set_hierarchy_depth(depth + 1);
+        for cell in table.cells.data.iter_mut().flatten() {
+            repair_cell(cell, depth + 1, seen);
+        }
+    }
+}
+
+fn place(pos: &CellPos) -> String {
+    if pos.is_root() {
+        "the highest cell".into()
+    } else {
+        format!("cell {pos}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::arrow::{Arrow, Waypoint};
+
+    // a highest cell with a 1x2 table and an arrow between the two inner cells
+    fn document() -> Data {
+        let mut data = Data::new();
+        data.cell.create_table();
+        data.cell.table.as_mut().unwrap().cells.add_col(1);
+        let (a, b) = (
+            data.get_cell(&CellPos::new().child(0, 0))
+                .unwrap()
+                .id
+                .clone(),
+            data.get_cell(&CellPos::new().child(0, 1))
+                .unwrap()
+                .id
+                .clone(),
+        );
+        data.configuration.layers[0].arrows.push(Arrow::new(a, b));
+        data
+    }
+
+    #[test]
+    fn valid_document() {
+        assert!(check(&document()).is_empty());
+    }
+
+    #[test]
+    fn removed_cell_leaves_dangling_arrow() {
+        let mut data = document();
+        let removed = data.cell.table.as_mut().unwrap().cells.remove_col(1);
+        assert_eq!(
+            check(&data),
+            [Problem::DanglingArrow {
+                layer: 0,
+                arrow: 0,
+                missing: vec![removed[0].id.clone()],
+            }]
+        );
+
+        let mut reattached = data.clone();
+        let root = reattached.cell.id.clone();
+        repair(&mut reattached, &DanglingArrows::Reattach(root.clone()));
+        assert!(check(&reattached).is_empty());
+        assert_eq!(reattached.configuration.layers[0].arrows[0].to, root);
+
+        repair(&mut data, &DanglingArrows::Remove);
+        assert!(check(&data).is_empty());
+        assert!(data.configuration.layers[0].arrows.is_empty());
+    }
+
+    #[test]
+    fn removed_cell_leaves_dangling_waypoints() {
+        let mut data = document();
+        let cells = &mut data.cell.table.as_mut().unwrap().cells;
+        cells.add_row(1);
+        let [kept, gone] =
+            [cells.data[0][0].id.clone(), cells.data[1][0].id.clone()].map(|cell| Waypoint {
+                cell,
+                x: 0.5,
+                y: 0.5,
+            });
+        cells.remove_row(1);
+        data.configuration.layers[0].arrows[0].waypoints = vec![kept.clone(), gone];
+        assert_eq!(
+            check(&data),
+            [Problem::DanglingWaypoints {
+                layer: 0,
+                arrow: 0,
+                count: 1,
+            }]
+        );
+
+        repair(&mut data, &DanglingArrows::Remove);
+        assert!(check(&data).is_empty());
+        assert_eq!(data.configuration.layers[0].arrows[0].waypoints, [kept]);
+    }
+
+    #[test]
+    fn empty_table() {
+        let mut data = document();
+        data.cell.table.as_mut().unwrap().cells.data.clear();
+        data.configuration.layers[0].arrows.clear();
+        let problems = check(&data);
+        assert_eq!(
+            problems,
+            [Problem::MalformedTable {
+                pos: CellPos::new(),
+                rows: vec![],
+            }]
+        );
+        assert_eq!(
+            problems[0].to_string(),
+            "the table of the highest cell has no cells"
+        );
+
+        assert!(repair_tables(&mut data));
+        assert!(check(&data).is_empty());
+        let cells = &data.cell.table.as_ref().unwrap().cells;
+        assert_eq!((cells.rows(), cells.cols()), (1, 1));
+    }
+
+    #[test]
+    fn empty_row() {
+        let mut data = document();
+        let cells = &mut data.cell.table.as_mut().unwrap().cells;
+        cells.data.push(vec![]);
+        assert_eq!(
+            check(&data),
+            [Problem::MalformedTable {
+                pos: CellPos::new(),
+                rows: vec![2, 0],
+            }]
+        );
+
+        repair(&mut data, &DanglingArrows::Remove);
+        assert!(check(&data).is_empty());
+        let cells = &data.cell.table.as_ref().unwrap().cells;
+        assert_eq!((cells.rows(), cells.cols()), (2, 2));
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let mut data = document();
+        let inner = CellPos::new().child(0, 1);
+        let cell = data.get_cell_mut(&inner).unwrap();
+        cell.create_table();
+        let cells = &mut cell.table.as_mut().unwrap().cells;
+        cells.add_row(1);
+        cells.add_col(1);
+        cells.data[0].pop();
+        let problems = check(&data);
+        assert_eq!(
+            problems,
+            [Problem::MalformedTable {
+                pos: inner.clone(),
+                rows: vec![1, 2],
+            }]
+        );
+        assert_eq!(
+            problems[0].to_string(),
+            "the table of cell 0,1 has rows of 1, 2 cells"
+        );
+
+        // the arrow stays, no cell is lost
+        repair(&mut data, &DanglingArrows::Remove);
+        assert!(check(&data).is_empty());
+        assert_eq!(data.configuration.layers[0].arrows.len(), 1);
+        let cells = &data.get_cell(&inner).unwrap().table.as_ref().unwrap().cells;
+        assert_eq!((cells.rows(), cells.cols()), (2, 2));
+        assert!(!repair_tables(&mut data));
+    }
+
+    #[test]
+    fn duplicate_ids_and_depths() {
+        let mut data = document();
+        data.configuration.layers[0].arrows.clear();
+        let cells = &mut data.cell.table.as_mut().unwrap().cells;
+        cells.data[0][1].id = cells.data[0][0].id.clone();
+        cells.data[0][1].hierarchy_depth = 5;
+        let problems = check(&data);
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(problems[0], Problem::WrongDepth { stored: 5, .. }));
+        assert!(matches!(problems[1], Problem::DuplicateId { .. }));
+
+        repair(&mut data, &DanglingArrows::Remove);
+        assert!(check(&data).is_empty());
+    }
+}
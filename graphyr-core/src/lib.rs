@@ -0,0 +1,65 @@
+//! The document model of Graphyr: plain data structures with their editing operations,
+//! the versioned file format, integrity checks, arrow routing and exporting,
+//! all usable without any UI.
+
+use cell::{Cell, CellId, CellPos};
+use configuration::Configuration;
+use serde::{Deserialize, Serialize};
+
+pub mod cell;
+pub mod color;
+pub mod configuration;
+pub mod drawing;
+pub mod export;
+pub mod format;
+pub mod integrity;
+pub mod routing;
+pub mod table;
+
+// this is a main centralized storage
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Data {
+    // our hierarchy starts with a single cell
+    pub cell: Cell,
+    pub configuration: Configuration,
+}
+
+impl Data {
+    pub fn new() -> Self {
+        Self {
+            cell: Cell::new(None, 0),
+            configuration: Configuration::new(),
+        }
+    }
+
+    // None when the place doesn't exist (anymore)
+    pub fn get_cell(&self, place: &CellPos) -> Option<&Cell> {
+        let first = place.top();
+        assert!(first == (0, 0));
+        self.cell.get_inner_cell(place.lower())
+    }
+
+    pub fn get_cell_mut(&mut self, place: &CellPos) -> Option<&mut Cell> {
+        let first = place.top();
+        assert!(first == (0, 0));
+        self.cell.get_inner_cell_mut(place.lower())
+    }
+
+    // falls back to the highest cell when the place became invalid (e.g. after its row was removed)
+    pub fn get_cell_or_highest(&self, place: &CellPos) -> (&Cell, CellPos) {
+        match self.get_cell(place) {
+            Some(cell) => (cell, place.clone()),
+            None => (&self.cell, CellPos::new()),
+        }
+    }
+
+    pub fn find_pos(&self, id: &CellId) -> Option<CellPos> {
+        self.cell.find_pos(id, CellPos::new())
+    }
+}
+
+impl Default for Data {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+use kurbo::{CubicBez, ParamCurve as _, Point, Rect};
+
+use super::Segment;
+
+/// Tensions that are tried for every part of the curve, the first one that keeps clear of the
+/// obstacles is used. 0 is the straight line between the points, which the router already
+/// checked.
+const TENSIONS: [f64; 3] = [1.0, 0.5, 0.0];
+
+/// Points checked along every part of the curve.
+const SAMPLES: usize = 16;
+
+/// A Catmull-Rom spline through the points, as one cubic Bézier curve between every two of
+/// them. Parts that would bend into the `obstacles` or closer than half of `margin` to them
+/// are flattened, the points themselves are `margin` away from them.
+pub fn curve_through(points: &[Point], obstacles: &[Rect], margin: f64) -> Vec<CubicBez> {
+    let expanded = obstacles
+        .iter()
+        .map(|obstacle| obstacle.inflate(margin / 2.0, margin / 2.0))
+        .collect::<Vec<_>>();
+    let last = points.len() - 1;
+    (0..last)
+        .map(|i| {
+            let (p1, p2) = (points[i], points[i + 1]);
+            // the ends are repeated, so the curve starts and ends along its first and last part
+            let p0 = points[i.saturating_sub(1)];
+            let p3 = points[(i + 2).min(last)];
+            TENSIONS
+                .into_iter()
+                .map(|tension| {
+                    CubicBez::new(
+                        p1,
+                        p1 + (p2 - p0) * tension / 6.0,
+                        p2 - (p3 - p1) * tension / 6.0,
+                        p2,
+                    )
+                })
+                .find(|cubic| is_clear(cubic, &expanded))
+                .unwrap_or_else(|| CubicBez::new(p1, p1, p2, p2))
+        })
+        .collect()
+}
+
+fn is_clear(cubic: &CubicBez, obstacles: &[Rect]) -> bool {
+    // the straight line is accepted as it is, it may touch the margin in narrow gaps
+    if cubic.p1 == cubic.p0 && cubic.p2 == cubic.p3 {
+        return true;
+    }
+    (0..SAMPLES).all(|i| {
+        let segment = Segment {
+            p1: cubic.eval(i as f64 / SAMPLES as f64),
+            p2: cubic.eval((i + 1) as f64 / SAMPLES as f64),
+        };
+        obstacles.iter().all(|obstacle| {
+            !obstacle.contains(segment.p1) && segment.intersect_rect(obstacle).is_none()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_through_points_and_keeps_clear() {
+        let points = [
+            Point::new(0., 0.),
+            Point::new(100., 0.),
+            Point::new(100., 100.),
+        ];
+        let free = curve_through(&points, &[], 10.);
+        assert_eq!(free.len(), 2);
+        assert_eq!(free[0].p0, points[0]);
+        assert_eq!(free[0].p3, points[1]);
+        assert_eq!(free[1].p3, points[2]);
+        // the curve bends out of the corner
+        assert!(free[0].p2.y < 0.);
+
+        let obstacle = Rect::new(40., -30., 90., -12.);
+        let curve = curve_through(&points, &[obstacle], 10.);
+        assert!(curve[0].p2.y > free[0].p2.y);
+        let expanded = obstacle.inflate(5., 5.);
+        for i in 0..=SAMPLES {
+            assert!(!expanded.contains(curve[0].eval(i as f64 / SAMPLES as f64)));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use kurbo::Rect;
+
+use crate::cell::CellId;
+
+/// The cells sorted by their left side, so only the cells in the horizontal range of a line
+/// have to be checked for collisions with it.
+pub struct ObstacleIndex {
+    obstacles: Vec<(CellId, Rect)>,
+    indices: HashMap<CellId, usize>,
+    // no cell starts further left than this from its right side
+    max_width: f64,
+}
+
+impl ObstacleIndex {
+    pub fn new(positions: &HashMap<CellId, Rect>) -> Self {
+        let mut obstacles = positions
+            .iter()
+            .map(|(id, rect)| (id.clone(), *rect))
+            .collect::<Vec<_>>();
+        // the map has no order, the routes should not depend on it
+        obstacles.sort_by(|a, b| a.1.x0.total_cmp(&b.1.x0).then(a.1.y0.total_cmp(&b.1.y0)));
+        let max_width = obstacles
+            .iter()
+            .map(|(_, rect)| rect.width())
+            .fold(0.0, f64::max);
+        let indices = obstacles
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (id.clone(), i))
+            .collect();
+        Self {
+            obstacles,
+            indices,
+            max_width,
+        }
+    }
+
+    pub fn obstacles(&self) -> &[(CellId, Rect)] {
+        &self.obstacles
+    }
+
+    pub fn index_of(&self, id: &CellId) -> Option<usize> {
+        self.indices.get(id).copied()
+    }
+
+    /// The indices of the cells that overlap `area`, found lazily, so checks that stop at the
+    /// first collision don't look at the rest.
+    pub fn near(&self, area: Rect) -> impl Iterator<Item = usize> + '_ {
+        let start = self
+            .obstacles
+            .partition_point(|(_, rect)| rect.x0 < area.x0 - self.max_width);
+        let end = self
+            .obstacles
+            .partition_point(|(_, rect)| rect.x0 <= area.x1);
+        (start..end.max(start)).filter(move |i| {
+            let rect = self.obstacles[*i].1;
+            rect.x1 >= area.x0 && rect.y0 <= area.y1 && rect.y1 >= area.y0
+        })
+    }
+}
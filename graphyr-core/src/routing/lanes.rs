@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use kurbo::{Point, Vec2};
+
+/// Segments closer to each other than this run along the same line.
+const SAME_LINE: f64 = 0.5;
+
+/// Shorter overlaps are only ends touching each other, not a shared part.
+const MIN_OVERLAP: f64 = 1.0;
+
+// a segment of one of the routes
+struct Part {
+    route: usize,
+    index: usize,
+    a: Point,
+    b: Point,
+}
+
+/// Moves the parts of the routes that run along the same line into parallel lanes `spacing`
+/// apart, e.g. the routes of arrows between the same cells, which would cover each other.
+///
+/// The lanes are ordered by the side the routes come from and go to, so routes that stay on
+/// one side of the others don't cross them. The bends of a route move to where its shifted
+/// segments meet.
+///
+/// Routes with the same key share one lane, they are never moved apart.
+pub fn separate(routes: &mut [Vec<Point>], keys: &[usize], spacing: f64) {
+    let parts = routes
+        .iter()
+        .enumerate()
+        .flat_map(|(route, points)| {
+            points
+                .windows(2)
+                .enumerate()
+                .filter(|(_, segment)| segment[0] != segment[1])
+                .map(move |(index, segment)| Part {
+                    route,
+                    index,
+                    a: segment[0],
+                    b: segment[1],
+                })
+        })
+        .collect::<Vec<_>>();
+
+    // the parts on the same line are joined into groups
+    let mut parent = (0..parts.len()).collect::<Vec<_>>();
+    for i in 0..parts.len() {
+        for j in i + 1..parts.len() {
+            if keys[parts[i].route] != keys[parts[j].route] && is_shared(&parts[i], &parts[j]) {
+                let (root_i, root_j) = (root(&mut parent, i), root(&mut parent, j));
+                parent[root_i] = root_j;
+            }
+        }
+    }
+    let mut groups = HashMap::<usize, Vec<usize>>::new();
+    for part in 0..parts.len() {
+        groups
+            .entry(root(&mut parent, part))
+            .or_default()
+            .push(part);
+    }
+
+    let mut offsets = routes
+        .iter()
+        .map(|points| vec![Vec2::ZERO; points.len().saturating_sub(1)])
+        .collect::<Vec<_>>();
+    for group in groups.values() {
+        let mut members = group
+            .iter()
+            .map(|i| keys[parts[*i].route])
+            .collect::<Vec<_>>();
+        members.sort();
+        members.dedup();
+        if members.len() < 2 {
+            continue;
+        }
+
+        let reference = &parts[group[0]];
+        let mut direction = (reference.b - reference.a).normalize();
+        if direction.x < -1e-9 || (direction.x.abs() <= 1e-9 && direction.y < 0.) {
+            direction = -direction;
+        }
+        let normal = Vec2::new(-direction.y, direction.x);
+        // how far the routes of the lane are on the side of the normal before and after the
+        // group
+        let side = |key: usize| {
+            group
+                .iter()
+                .map(|i| &parts[*i])
+                .filter(|part| keys[part.route] == key)
+                .map(|part| {
+                    let points = &routes[part.route];
+                    let before = part.index.checked_sub(1).map_or(part.a, |i| points[i]);
+                    let after = points.get(part.index + 2).copied().unwrap_or(part.b);
+                    (before - reference.a).dot(normal) + (after - reference.a).dot(normal)
+                })
+                .sum::<f64>()
+        };
+        let mut sides = members
+            .iter()
+            .map(|key| (side(*key), *key))
+            .collect::<Vec<_>>();
+        sides.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        let middle = (sides.len() - 1) as f64 / 2.;
+        for (lane, (_, key)) in sides.into_iter().enumerate() {
+            let offset = normal * (lane as f64 - middle) * spacing;
+            for part in group.iter().map(|i| &parts[*i]) {
+                if keys[part.route] == key {
+                    offsets[part.route][part.index] = offset;
+                }
+            }
+        }
+    }
+
+    for (points, offsets) in routes.iter_mut().zip(&offsets) {
+        shift(points, offsets);
+    }
+}
+
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn is_shared(p: &Part, q: &Part) -> bool {
+    let direction = (p.b - p.a).normalize();
+    if direction.cross((q.b - q.a).normalize()).abs() > 1e-3
+        || direction.cross(q.a - p.a).abs() > SAME_LINE
+    {
+        return false;
+    }
+    let length = (p.b - p.a).dot(direction);
+    let (q0, q1) = ((q.a - p.a).dot(direction), (q.b - p.a).dot(direction));
+    length.min(q0.max(q1)) - q0.min(q1).max(0.) > MIN_OVERLAP
+}
+
+// moves every segment by its offset, the ends of the route move with their segments
+fn shift(points: &mut [Point], offsets: &[Vec2]) {
+    if offsets.iter().all(|offset| *offset == Vec2::ZERO) {
+        return;
+    }
+    let original = points.to_vec();
+    let last = points.len() - 1;
+    for (i, point) in points.iter_mut().enumerate() {
+        let before = (i > 0).then(|| (original[i] - original[i - 1], offsets[i - 1]));
+        let after = (i < last).then(|| (original[i + 1] - original[i], offsets[i]));
+        *point += match (before, after) {
+            (Some((d1, o1)), Some((d2, o2))) => miter(d1, o1, d2, o2),
+            (Some((_, offset)), None) | (None, Some((_, offset))) => offset,
+            (None, None) => Vec2::ZERO,
+        };
+    }
+}
+
+// the offset of the bend where the two shifted segments meet
+fn miter(d1: Vec2, o1: Vec2, d2: Vec2, o2: Vec2) -> Vec2 {
+    let cross = d1.cross(d2);
+    if cross.abs() > 1e-9 {
+        let offset = o1 + d1 * ((o2 - o1).cross(d2) / cross);
+        // almost straight bends would move far away
+        if offset.length() <= 3. * o1.length().max(o2.length()) {
+            return offset;
+        }
+    }
+    (o1 + o2) / 2.
+}
+
+/// The points where every route crosses one of the routes before it, it hops over them
+/// there. Crossings closer than `clearance` to a bend of either route are left out, there
+/// is no room for a hop. Routes with the same key don't hop over each other.
+pub fn crossings(routes: &[&[Point]], keys: &[usize], clearance: f64) -> Vec<Vec<Point>> {
+    let far_from_ends = |point: Point, segment: &[Point]| {
+        point.distance(segment[0]) >= clearance && point.distance(segment[1]) >= clearance
+    };
+    routes
+        .iter()
+        .enumerate()
+        .map(|(i, route)| {
+            route
+                .windows(2)
+                .flat_map(|segment| {
+                    routes[..i]
+                        .iter()
+                        .zip(keys)
+                        .filter(move |(_, key)| **key != keys[i])
+                        .flat_map(|(earlier, _)| earlier.windows(2))
+                        .filter_map(move |other| {
+                            let point = intersection(segment, other)?;
+                            (far_from_ends(point, segment) && far_from_ends(point, other))
+                                .then_some(point)
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// where the two segments cross, parallel ones never do
+fn intersection(s: &[Point], t: &[Point]) -> Option<Point> {
+    let (d, e) = (s[1] - s[0], t[1] - t[0]);
+    let cross = d.cross(e);
+    if cross.abs() < 1e-9 {
+        return None;
+    }
+    let u = (t[0] - s[0]).cross(e) / cross;
+    let v = (t[0] - s[0]).cross(d) / cross;
+    ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)).then(|| s[0] + d * u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_shared_segments() {
+        let shared = vec![Point::new(0., 0.), Point::new(100., 0.)];
+        let mut routes = vec![
+            shared.clone(),
+            shared,
+            vec![Point::new(50., 50.), Point::new(50., 20.)],
+        ];
+        let original = routes.clone();
+        separate(&mut routes, &[0, 1, 2], 6.);
+        assert_eq!(routes[0], [Point::new(0., -3.), Point::new(100., -3.)]);
+        assert_eq!(routes[1], [Point::new(0., 3.), Point::new(100., 3.)]);
+        // alone on its line
+        assert_eq!(routes[2], [Point::new(50., 50.), Point::new(50., 20.)]);
+
+        // routes with the same key are one lane
+        let mut routes = original.clone();
+        separate(&mut routes, &[0, 0, 2], 6.);
+        assert_eq!(routes, original);
+
+        // the bend follows the shifted segments
+        let mut routes = vec![
+            vec![
+                Point::new(0., 0.),
+                Point::new(100., 0.),
+                Point::new(100., 100.),
+            ],
+            vec![
+                Point::new(0., 0.),
+                Point::new(100., 0.),
+                Point::new(100., -100.),
+            ],
+        ];
+        separate(&mut routes, &[0, 1], 6.);
+        // the route going down keeps to the lower side, so they don't cross
+        assert_eq!(routes[0][1], Point::new(100., 3.));
+        assert_eq!(routes[1][1], Point::new(100., -3.));
+    }
+
+    #[test]
+    fn finds_crossings_away_from_bends() {
+        let horizontal = [Point::new(0., 0.), Point::new(100., 0.)];
+        let vertical = [Point::new(50., -50.), Point::new(50., 50.)];
+        let corner = [Point::new(95., -50.), Point::new(95., 50.)];
+        let found = crossings(&[&horizontal, &vertical, &corner], &[0, 1, 2], 12.);
+        assert_eq!(found[0], []);
+        assert_eq!(found[1], [Point::new(50., 0.)]);
+        // too close to the end of the horizontal line
+        assert_eq!(found[2], []);
+    }
+}
@@ -0,0 +1,787 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use kurbo::{
+    BezPath, Circle, CubicBez, ParamCurve as _, ParamCurveArclen as _, ParamCurveNearest as _,
+    Point, Rect, Shape as _, Size, Vec2,
+};
+
+use crate::{
+    cell::CellId,
+    configuration::arrow::{Arrow, Head, Routing},
+};
+
+mod curve;
+mod index;
+mod lanes;
+mod orthogonal;
+mod router;
+mod waypoint;
+
+pub use router::{Route, Router, Trunk};
+pub use waypoint::{pin, place};
+
+/// Radius of the rounded corners of paths made by the given routing.
+fn corner_radius(routing: Routing) -> f64 {
+    match routing {
+        Routing::Direct | Routing::Curved => 0.0,
+        Routing::Orthogonal => 6.0,
+    }
+}
+
+pub struct Segment {
+    pub p1: Point,
+    pub p2: Point,
+}
+
+impl Segment {
+    /// Returns the intersection point between the segment and the rectangle's boundary.
+    /// If the segment starts inside the rectangle, returns the exit point; otherwise, the entry point.
+    /// Uses the Liang-Barsky algorithm for parametric clipping.
+    pub fn intersect_rect(&self, rect: &Rect) -> Option<Point> {
+        let dx = self.p2.x - self.p1.x;
+        let dy = self.p2.y - self.p1.y;
+        let mut t_min = 0.0;
+        let mut t_max = 1.0;
+
+        // Updates t_min and t_max for a boundary defined by (p, q).
+        // Returns false if the segment is parallel to the boundary and outside.
+        let update = |p: f64, q: f64, t_min: &mut f64, t_max: &mut f64| -> bool {
+            if p.abs() < f64::EPSILON {
+                if q < 0.0 {
+                    return false;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    if t > *t_min {
+                        *t_min = t;
+                    }
+                } else if t < *t_max {
+                    *t_max = t;
+                }
+            }
+            true
+        };
+
+        // Left boundary: x = rect.x0
+        if !update(-dx, self.p1.x - rect.x0, &mut t_min, &mut t_max) {
+            return None;
+        }
+        // Right boundary: x = rect.x1
+        if !update(dx, rect.x1 - self.p1.x, &mut t_min, &mut t_max) {
+            return None;
+        }
+        // Top boundary: y = rect.y0
+        if !update(-dy, self.p1.y - rect.y0, &mut t_min, &mut t_max) {
+            return None;
+        }
+        // Bottom boundary: y = rect.y1
+        if !update(dy, rect.y1 - self.p1.y, &mut t_min, &mut t_max) {
+            return None;
+        }
+
+        if t_min > t_max {
+            return None;
+        }
+
+        // Determine which intersection to return:
+        // if p1 is inside, return the exit point (t_max); otherwise, the entry point (t_min).
+        let inside = self.p1.x >= rect.x0
+            && self.p1.x <= rect.x1
+            && self.p1.y >= rect.y0
+            && self.p1.y <= rect.y1;
+        let t = if inside { t_max } else { t_min };
+
+        if !(0.0..=1.0).contains(&t) {
+            return None;
+        }
+
+        Some(Point {
+            x: self.p1.x + t * dx,
+            y: self.p1.y + t * dy,
+        })
+    }
+}
+
+/// Radius of the half circles a line jumps over the arrows it crosses with.
+pub const HOP_RADIUS: f64 = 4.0;
+
+/// The path through the given points, its corners are rounded with `corner_radius`
+/// (limited to half of the shorter neighboring segment), 0 keeps them sharp. It hops over
+/// the `hops` on its straight parts.
+pub fn line_path(points: &[Point], corner_radius: f64, hops: &[Point]) -> BezPath {
+    let mut path = BezPath::new();
+    let Some(first) = points.first() else {
+        return path;
+    };
+    path.move_to(*first);
+    // where the current straight part starts
+    let mut from = *first;
+    for corner in points.windows(3) {
+        let (before, at, after) = (corner[0], corner[1], corner[2]);
+        let radius = corner_radius
+            .min(before.distance(at) / 2.)
+            .min(at.distance(after) / 2.);
+        if radius <= 0. {
+            line_with_hops(&mut path, from, at, hops);
+            from = at;
+            continue;
+        }
+        line_with_hops(
+            &mut path,
+            from,
+            at + (before - at).normalize() * radius,
+            hops,
+        );
+        from = at + (after - at).normalize() * radius;
+        path.quad_to(at, from);
+    }
+    if points.len() > 1 {
+        line_with_hops(&mut path, from, points[points.len() - 1], hops);
+    }
+    path
+}
+
+// a straight line, with a half circle over every one of the `hops` that lies on it
+fn line_with_hops(path: &mut BezPath, from: Point, to: Point, hops: &[Point]) {
+    let length = from.distance(to);
+    if length > 0. {
+        let direction = (to - from) / length;
+        // always bulging up, or to the right on vertical lines
+        let mut normal = Vec2::new(direction.y, -direction.x);
+        if normal.y > 0. || (normal.y == 0. && normal.x < 0.) {
+            normal = -normal;
+        }
+        // how far along the line the hops on it are
+        let mut on_line = hops
+            .iter()
+            .map(|hop| (*hop, (*hop - from).dot(direction)))
+            .filter(|(hop, t)| {
+                *t > HOP_RADIUS
+                    && *t < length - HOP_RADIUS
+                    && hop.distance(from + direction * *t) < 0.5
+            })
+            .map(|(_, t)| t)
+            .collect::<Vec<_>>();
+        on_line.sort_by(f64::total_cmp);
+        let mut free_from = 0.;
+        for t in on_line {
+            // hops too close to the previous one are covered by it
+            if t - HOP_RADIUS < free_from {
+                continue;
+            }
+            let (start, end) = (
+                from + direction * (t - HOP_RADIUS),
+                from + direction * (t + HOP_RADIUS),
+            );
+            // the usual cubic approximation of a half circle
+            let bulge = normal * HOP_RADIUS * 4. / 3.;
+            path.line_to(start);
+            path.curve_to(start + bulge, end + bulge, end);
+            free_from = t + HOP_RADIUS;
+        }
+    }
+    path.line_to(to);
+}
+
+/// The outline of an arrow end.
+pub struct HeadShape {
+    pub path: BezPath,
+    // filled shapes are painted with the color, the others are stroked like the line
+    pub filled: bool,
+    // where the line has to stop, so it doesn't go through the shape
+    pub line_end: Point,
+}
+
+/// The head of the given type with its tip at `to`, for a line of the given width
+/// coming from `from`.
+pub fn head_shape(head: Head, from: Point, to: Point, width: f64) -> Option<HeadShape> {
+    if head == Head::None || from == to {
+        return None;
+    }
+    // 8 for the default width of 2, as the heads always were
+    let length = 4. + 2. * width;
+    let direction = (to - from).normalize();
+    let side = Vec2::new(-direction.y, direction.x);
+    // a 30° triangle, its sides have the given length
+    let angle = std::f64::consts::PI / 6.;
+    let (back, spread) = (length * angle.cos(), length * angle.sin());
+
+    let mut path = BezPath::new();
+    let (filled, line_end) = match head {
+        Head::None => unreachable!(),
+        Head::Open | Head::Filled => {
+            let base = to - direction * back;
+            path.move_to(base + side * spread);
+            path.line_to(to);
+            path.line_to(base - side * spread);
+            if head == Head::Filled {
+                path.close_path();
+                (true, base)
+            } else {
+                (false, to)
+            }
+        }
+        Head::Diamond => {
+            let middle = to - direction * length;
+            let end = to - direction * 2. * length;
+            path.move_to(to);
+            path.line_to(middle + side * spread);
+            path.line_to(end);
+            path.line_to(middle - side * spread);
+            path.close_path();
+            (true, end)
+        }
+        Head::CrowsFoot => {
+            let fork = to - direction * length;
+            for toe in [to + side * length * 0.6, to, to - side * length * 0.6] {
+                path.move_to(fork);
+                path.line_to(toe);
+            }
+            (false, to)
+        }
+        Head::Circle => {
+            let radius = length * 0.5;
+            path = Circle::new(to - direction * radius, radius).to_path(0.1);
+            (false, to - direction * 2. * radius)
+        }
+    };
+    Some(HeadShape {
+        path,
+        filled,
+        line_end,
+    })
+}
+
+/// The line of a routed arrow together with its heads, the line is shortened where a head
+/// covers it and hops over the arrows it crosses. Curved arrows are drawn as a spline
+/// through the path that keeps clear of the cells in `positions`, their heads follow the
+/// direction of the curve at its ends. Bundled arrows only get their branches here.
+pub fn arrow_shapes(
+    route: &Route,
+    arrow: &Arrow,
+    routing: Routing,
+    positions: &HashMap<CellId, Rect>,
+) -> (BezPath, Vec<HeadShape>) {
+    let path = &route.points;
+    if routing == Routing::Curved {
+        let obstacles = positions
+            .iter()
+            .filter(|(id, _)| **id != arrow.from && **id != arrow.to)
+            .map(|(_, rect)| *rect)
+            .collect::<Vec<_>>();
+        return curved_shapes(path, arrow, &obstacles);
+    }
+
+    let mut line = path.to_vec();
+    let mut heads = vec![];
+    let last = path.len() - 1;
+    if let Some(head) = head_shape(arrow.start, path[1], path[0], arrow.width) {
+        line[0] = head.line_end;
+        heads.push(head);
+    }
+    if let Some(head) = head_shape(arrow.end, path[last - 1], path[last], arrow.width) {
+        line[last] = head.line_end;
+        heads.push(head);
+    }
+    let radius = corner_radius(routing);
+    let Some(trunk) = &route.trunk else {
+        return (line_path(&line, radius, &route.hops), heads);
+    };
+    // the trunk is painted once for the whole bundle by `trunk_line`
+    let mut path = line_path(&line[..=trunk.range.start], radius, &route.hops);
+    let end = line_path(&line[trunk.range.end - 1..], radius, &route.hops);
+    path.extend(end.elements().iter().copied());
+    (path, heads)
+}
+
+/// The line of the trunk of a bundle and its width, which grows with the number of arrows
+/// in it. Only the first arrow of the bundle has it.
+pub fn trunk_line(route: &Route, arrow: &Arrow, routing: Routing) -> Option<(BezPath, f64)> {
+    let trunk = route.trunk.as_ref().filter(|trunk| trunk.first)?;
+    let line = line_path(
+        &route.points[trunk.range.clone()],
+        corner_radius(routing),
+        &route.hops,
+    );
+    Some((line, arrow.width * (1. + (trunk.arrows as f64).ln())))
+}
+
+fn curved_shapes(path: &[Point], arrow: &Arrow, obstacles: &[Rect]) -> (BezPath, Vec<HeadShape>) {
+    let mut curve = curve::curve_through(path, obstacles, arrow.padding);
+    let mut heads = vec![];
+
+    let first = curve[0];
+    if let Some(head) = head_shape(arrow.start, leaving(first), first.p0, arrow.width) {
+        let cut = first.p0.distance(head.line_end);
+        curve[0] = first.subsegment(first.inv_arclen(cut, ARCLEN_ACCURACY)..1.0);
+        heads.push(head);
+    }
+    let last = curve.len() - 1;
+    let reversed = CubicBez::new(
+        curve[last].p3,
+        curve[last].p2,
+        curve[last].p1,
+        curve[last].p0,
+    );
+    if let Some(head) = head_shape(arrow.end, leaving(reversed), reversed.p0, arrow.width) {
+        let cut = reversed.p0.distance(head.line_end);
+        let t = 1.0 - reversed.inv_arclen(cut, ARCLEN_ACCURACY);
+        curve[last] = curve[last].subsegment(0.0..t);
+        heads.push(head);
+    }
+
+    let mut line = BezPath::new();
+    line.move_to(curve[0].p0);
+    for cubic in &curve {
+        line.curve_to(cubic.p1, cubic.p2, cubic.p3);
+    }
+    (line, heads)
+}
+
+const ARCLEN_ACCURACY: f64 = 1e-3;
+
+// a point in the direction the curve leaves its start in
+fn leaving(cubic: CubicBez) -> Point {
+    [cubic.p1, cubic.p2, cubic.p3]
+        .into_iter()
+        .find(|point| *point != cubic.p0)
+        .unwrap_or(cubic.p3)
+}
+
+/// Where to put a label of the given size on an arrow path: on its longest segment, as close
+/// to the middle of it as possible without covering any of the `obstacles`.
+pub fn label_rect(path: &[Point], size: Size, obstacles: &HashMap<CellId, Rect>) -> Option<Rect> {
+    // fractions of the segment that are tried, the middle is preferred
+    const PLACES: [f64; 7] = [0.5, 0.4, 0.6, 0.3, 0.7, 0.2, 0.8];
+
+    let (start, end) = path
+        .windows(2)
+        .map(|segment| (segment[0], segment[1]))
+        .max_by(|a, b| a.0.distance(a.1).total_cmp(&b.0.distance(b.1)))?;
+    let place = |t: f64| Rect::from_center_size(start.lerp(end, t), size);
+    let free = PLACES.into_iter().map(place).find(|rect| {
+        obstacles
+            .values()
+            .all(|obstacle| rect.intersect(*obstacle).area() == 0.)
+    });
+    // everything is covered, the middle is still the most readable place
+    Some(free.unwrap_or_else(|| place(0.5)))
+}
+
+/// Distance from the point to the closest part of the line, for finding the arrow under
+/// the pointer.
+pub fn distance_to_line(line: &BezPath, point: Point) -> f64 {
+    line.segments()
+        .map(|segment| segment.nearest(point, ARCLEN_ACCURACY).distance_sq)
+        .fold(f64::INFINITY, f64::min)
+        .sqrt()
+}
+
+/// Whether the point is inside of the rect or on its border.
+fn encloses(rect: &Rect, point: Point) -> bool {
+    (rect.x0..=rect.x1).contains(&point.x) && (rect.y0..=rect.y1).contains(&point.y)
+}
+
+/// A line from the border of `rect` to the closest border of `bounds`, used for arrows whose
+/// other end is not visible.
+pub fn stub_line(rect: &Rect, bounds: &Rect) -> (Point, Point) {
+    let center = rect.center();
+    let candidates = [
+        (
+            center.x - bounds.x0,
+            Point::new(rect.x0, center.y),
+            Point::new(bounds.x0, center.y),
+        ),
+        (
+            bounds.x1 - center.x,
+            Point::new(rect.x1, center.y),
+            Point::new(bounds.x1, center.y),
+        ),
+        (
+            center.y - bounds.y0,
+            Point::new(center.x, rect.y0),
+            Point::new(center.x, bounds.y0),
+        ),
+        (
+            bounds.y1 - center.y,
+            Point::new(center.x, rect.y1),
+            Point::new(center.x, bounds.y1),
+        ),
+    ];
+    let (_, start, end) = candidates
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap();
+    (start, end)
+}
+
+/// Routes a direct path between the points, building the whole visibility graph for it.
+/// `Router` keeps the graph for routing more arrows.
+pub fn compute_path(
+    obstacles: &[Rect],
+    start: &Point,
+    goal: &Point,
+    margin: f64,
+) -> Option<Vec<Point>> {
+    let candidate_nodes = extract_candidate_nodes(obstacles, margin);
+    let (nodes, graph) = build_graph(&candidate_nodes, start, goal, obstacles, margin);
+    // Start is at index 0 and goal at index 1.
+    astar(&nodes, |i, edges| edges.extend_from_slice(&graph[i]), 0, 1)
+        .map(|path_indices| path_indices.into_iter().map(|i| nodes[i]).collect())
+}
+
+/// State used for the A* search.
+#[derive(Copy, Clone, Debug)]
+struct State {
+    position: usize,
+    g: f64, // actual cost from start
+    f: f64, // g + heuristic
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.f == other.f
+    }
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // We want a min-heap based on f-value.
+        other.f.partial_cmp(&self.f).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Perform A* search on the graph, `neighbors` adds the edges leaving a node.
+/// The heuristic used is the Euclidean distance from the current node to the goal.
+fn astar(
+    nodes: &[Point],
+    mut neighbors: impl FnMut(usize, &mut Vec<Edge>),
+    start_idx: usize,
+    goal_idx: usize,
+) -> Option<Vec<usize>> {
+    let n = nodes.len();
+    let mut dist = vec![f64::INFINITY; n];
+    let mut prev = vec![None; n];
+    let mut heap = BinaryHeap::new();
+    let mut edges = vec![];
+
+    dist[start_idx] = 0.0;
+    heap.push(State {
+        position: start_idx,
+        g: 0.0,
+        f: nodes[start_idx].distance(nodes[goal_idx]),
+    });
+
+    while let Some(State { position, g, f: _ }) = heap.pop() {
+        if position == goal_idx {
+            // Reconstruct path from start to goal.
+            let mut path = Vec::new();
+            let mut current = goal_idx;
+            while let Some(p) = prev[current] {
+                path.push(current);
+                current = p;
+            }
+            path.push(start_idx);
+            path.reverse();
+            return Some(path);
+        }
+
+        // If we've found a better path already, skip.
+        if g > dist[position] {
+            continue;
+        }
+
+        edges.clear();
+        neighbors(position, &mut edges);
+        for edge in &edges {
+            let next = edge.to;
+            let new_g = g + edge.cost;
+            if new_g < dist[next] {
+                dist[next] = new_g;
+                prev[next] = Some(position);
+                let new_f = new_g + nodes[next].distance(nodes[goal_idx]);
+                heap.push(State {
+                    position: next,
+                    g: new_g,
+                    f: new_f,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// An edge in the navigation graph.
+#[derive(Debug, Clone)]
+struct Edge {
+    to: usize,
+    cost: f64,
+}
+
+/// Build a graph from candidate nodes (including start and goal).
+/// Each edge is added only if the straight-line segment is collision-free.
+fn build_graph(
+    candidate_nodes: &[Point],
+    start: &Point,
+    goal: &Point,
+    obstacles: &[Rect],
+    margin: f64,
+) -> (Vec<Point>, Vec<Vec<Edge>>) {
+    let mut nodes = Vec::new();
+    // Insert start and goal at indices 0 and 1.
+    nodes.push(*start);
+    nodes.push(*goal);
+    // Then add the candidate nodes from obstacles.
+    nodes.extend_from_slice(candidate_nodes);
+
+    let n = nodes.len();
+    let mut graph: Vec<Vec<Edge>> = vec![vec![]; n];
+
+    // Check every pair of nodes.
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if is_edge_valid(&nodes[i], &nodes[j], obstacles.iter().copied(), margin) {
+                let dist = nodes[i].distance(nodes[j]);
+                graph[i].push(Edge { to: j, cost: dist });
+                graph[j].push(Edge { to: i, cost: dist });
+            }
+        }
+    }
+    (nodes, graph)
+}
+
+/// Checks if the straight-line segment between p0 and p1 is collision-free.
+fn is_edge_valid(
+    p0: &Point,
+    p1: &Point,
+    obstacles: impl IntoIterator<Item = Rect>,
+    margin: f64,
+) -> bool {
+    !obstacles
+        .into_iter()
+        .any(|obs| blocks(p0, p1, &obs, margin))
+}
+
+/// Checks if the obstacle is in the way of the segment between p0 and p1.
+/// If an edge touches an expanded obstacle, but both endpoints lie on the original boundary,
+/// we allow it (this is our “narrow gap” handling).
+fn blocks(p0: &Point, p1: &Point, obs: &Rect, margin: f64) -> bool {
+    // Use the expanded rectangle for collision detection.
+    let expanded = expand_rect(obs, margin);
+    let segment = Segment { p1: *p0, p2: *p1 };
+    segment.intersect_rect(&expanded).is_some() && segment.intersect_rect(obs).is_some()
+}
+
+/// Extract candidate nodes from obstacles.
+/// For each obstacle we take the corners of the expanded rectangle, and, if desired,
+/// add midpoints of the original edges (to allow path segments that pass between obstacles).
+fn extract_candidate_nodes(obstacles: &[Rect], margin: f64) -> Vec<Point> {
+    let mut nodes = Vec::new();
+    for obs in obstacles {
+        // Candidate nodes from the expanded rectangle corners:
+        let exp = expand_rect(obs, margin);
+        nodes.push(Point {
+            x: exp.x0,
+            y: exp.y0,
+        });
+        nodes.push(Point {
+            x: exp.x1,
+            y: exp.y0,
+        });
+        nodes.push(Point {
+            x: exp.x1,
+            y: exp.y1,
+        });
+        nodes.push(Point {
+            x: exp.x0,
+            y: exp.y1,
+        });
+
+        // Additionally, add the midpoints of the original edges.
+        // (This may be used in cases where the gap between obstacles is too narrow.)
+        let mid_top = Point {
+            x: (obs.x0 + obs.x1) / 2.0,
+            y: obs.y0,
+        };
+        let mid_bottom = Point {
+            x: (obs.x0 + obs.x1) / 2.0,
+            y: obs.y1,
+        };
+        let mid_left = Point {
+            x: obs.x0,
+            y: (obs.y0 + obs.y1) / 2.0,
+        };
+        let mid_right = Point {
+            x: obs.x1,
+            y: (obs.y0 + obs.y1) / 2.0,
+        };
+        nodes.push(mid_top);
+        nodes.push(mid_bottom);
+        nodes.push(mid_left);
+        nodes.push(mid_right);
+    }
+    nodes
+}
+
+/// Given a rectangle, return an “expanded” rectangle by subtracting margin from the
+/// minimum coordinates and adding margin to the maximum coordinates.
+fn expand_rect(rect: &Rect, margin: f64) -> Rect {
+    Rect {
+        x0: rect.x0 - margin,
+        y0: rect.y0 - margin,
+        x1: rect.x1 + margin,
+        y1: rect.y1 + margin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADS: [Head; 5] = [
+        Head::Open,
+        Head::Filled,
+        Head::Diamond,
+        Head::CrowsFoot,
+        Head::Circle,
+    ];
+
+    fn route(points: &[Point]) -> Route {
+        Route {
+            points: points.to_vec(),
+            unroutable: false,
+            hops: vec![],
+            trunk: None,
+        }
+    }
+
+    // an arrow with the same head at both ends
+    fn arrow(head: Head) -> Arrow {
+        Arrow {
+            start: head,
+            end: head,
+            ..Arrow::new(CellId::new(), CellId::new())
+        }
+    }
+
+    fn ends(line: &BezPath) -> (Point, Point) {
+        let first = line.segments().next().unwrap().start();
+        let last = line.segments().last().unwrap().end();
+        (first, last)
+    }
+
+    #[test]
+    fn heads_cut_back_the_line() {
+        let (from, to) = (Point::new(0., 0.), Point::new(100., 0.));
+        assert!(head_shape(Head::None, from, to, 2.).is_none());
+        assert!(head_shape(Head::Open, to, to, 2.).is_none());
+        for head in HEADS {
+            let shape = head_shape(head, from, to, 2.).unwrap();
+            let cut = to.x - shape.line_end.x;
+            assert_eq!(shape.line_end.y, 0.);
+            match head {
+                // the line goes up to the tip
+                Head::Open | Head::CrowsFoot => assert_eq!(cut, 0.),
+                _ => assert!(cut > 0., "{head:?} covers the line"),
+            }
+            assert_eq!(shape.filled, matches!(head, Head::Filled | Head::Diamond));
+            // the shape starts at the tip and doesn't go back past the end of the line
+            let bounds = shape.path.bounding_box();
+            assert!(bounds.x1 <= to.x + 1e-9 && bounds.x0 >= to.x - 2. * 8. - 1e-9);
+        }
+    }
+
+    #[test]
+    fn lines_end_at_both_heads() {
+        let points = [
+            Point::new(0., 0.),
+            Point::new(50., 0.),
+            Point::new(50., 80.),
+        ];
+        let (first, last) = (points[0], points[2]);
+        for head in HEADS {
+            let arrow = arrow(head);
+            let (line, heads) =
+                arrow_shapes(&route(&points), &arrow, Routing::Direct, &HashMap::new());
+            assert_eq!(heads.len(), 2);
+            let start = head_shape(head, points[1], first, arrow.width).unwrap();
+            let end = head_shape(head, points[1], last, arrow.width).unwrap();
+            assert_eq!(ends(&line), (start.line_end, end.line_end), "{head:?}");
+
+            // curves are cut by the same length along them, which is a bit more than the
+            // distance to the end as they bend
+            let (line, heads) =
+                arrow_shapes(&route(&points), &arrow, Routing::Curved, &HashMap::new());
+            assert_eq!(heads.len(), 2);
+            let (curve_start, curve_end) = ends(&line);
+            let cut = first.distance(start.line_end);
+            for distance in [first.distance(curve_start), last.distance(curve_end)] {
+                assert!(distance <= cut + 0.01 && distance >= cut * 0.9, "{head:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn no_heads_keep_the_points() {
+        let points = [
+            Point::new(0., 0.),
+            Point::new(50., 0.),
+            Point::new(50., 80.),
+        ];
+        let arrow = arrow(Head::None);
+        for routing in [Routing::Direct, Routing::Orthogonal, Routing::Curved] {
+            let (line, heads) = arrow_shapes(&route(&points), &arrow, routing, &HashMap::new());
+            assert!(heads.is_empty());
+            assert_eq!(ends(&line), (points[0], points[2]));
+        }
+        let (line, _) = arrow_shapes(&route(&points), &arrow, Routing::Direct, &HashMap::new());
+        let corners = line
+            .elements()
+            .iter()
+            .filter_map(|element| element.end_point())
+            .collect::<Vec<_>>();
+        assert_eq!(corners, points);
+    }
+
+    const LABEL: Size = Size::new(20., 10.);
+
+    #[test]
+    fn label_on_longest_segment() {
+        let path = [
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 100.),
+        ];
+        let rect = label_rect(&path, LABEL, &HashMap::new()).unwrap();
+        assert_eq!(rect.center(), Point::new(10., 50.));
+        assert_eq!(rect.size(), LABEL);
+        assert!(label_rect(&path[..1], LABEL, &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn label_moves_off_titles() {
+        let path = [Point::new(10., 0.), Point::new(10., 100.)];
+        let title = Rect::new(0., 45., 20., 55.);
+        let obstacles = HashMap::from([(CellId::new(), title)]);
+        let rect = label_rect(&path, LABEL, &obstacles).unwrap();
+        assert_eq!(rect.center(), Point::new(10., 40.));
+        assert_eq!(rect.intersect(title).area(), 0.);
+
+        // nothing is free, it stays in the middle
+        let obstacles = HashMap::from([(CellId::new(), Rect::new(0., 0., 20., 100.))]);
+        let rect = label_rect(&path, LABEL, &obstacles).unwrap();
+        assert_eq!(rect.center(), Point::new(10., 50.));
+    }
+}
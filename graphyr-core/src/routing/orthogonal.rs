@@ -0,0 +1,295 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use kurbo::{Point, Rect};
+
+use super::Segment;
+
+/// Extra cost of every bend, in the same units as the length of the path, so a path
+/// with fewer bends is preferred even when it's a bit longer.
+const BEND_COST: f64 = 40.0;
+
+/// Routes a path made only of horizontal and vertical segments from the border of `from`
+/// to the border of `to`, keeping `margin` away from the `obstacles` and using as few
+/// bends as possible.
+///
+/// The path runs on a sparse grid made of the lines around every obstacle (at `margin`)
+/// and the center lines of both ends. Between two neighboring lines a segment is either
+/// completely inside of an obstacle or completely outside of it, so checking the middle of
+/// every grid edge is enough. When `bounds` are given, the path stays inside of them.
+pub fn compute_orthogonal_path(
+    obstacles: &[Rect],
+    from: &Rect,
+    to: &Rect,
+    margin: f64,
+    bounds: Option<Rect>,
+) -> Option<Vec<Point>> {
+    let expanded = obstacles
+        .iter()
+        .map(|obstacle| obstacle.inflate(margin, margin))
+        .collect::<Vec<_>>();
+    let (start, goal) = (from.center(), to.center());
+
+    let mut xs = vec![start.x, goal.x];
+    let mut ys = vec![start.y, goal.y];
+    for rect in expanded.iter().chain([from, to]) {
+        xs.extend([rect.x0, rect.x1]);
+        ys.extend([rect.y0, rect.y1]);
+    }
+    for rect in [from, to] {
+        let outer = rect.inflate(margin, margin);
+        xs.extend([outer.x0, outer.x1]);
+        ys.extend([outer.y0, outer.y1]);
+    }
+    let xs = with_midpoints(xs);
+    let ys = with_midpoints(ys);
+
+    let grid = Grid {
+        xs: &xs,
+        ys: &ys,
+        obstacles: &expanded,
+        ends: [from, to],
+        bounds,
+    };
+    let start = grid.node(start)?;
+    let goal = grid.node(goal)?;
+    let nodes = grid.search(start, goal)?;
+
+    let mut path = nodes
+        .into_iter()
+        .map(|(x, y)| Point::new(xs[x], ys[y]))
+        .collect::<Vec<_>>();
+    remove_collinear(&mut path);
+    if path.len() < 2 {
+        return None;
+    }
+
+    // the path goes between the centers, it has to end at the borders instead
+    let last = path.len() - 1;
+    path[0] = Segment {
+        p1: path[0],
+        p2: path[1],
+    }
+    .intersect_rect(from)?;
+    path[last] = Segment {
+        p1: path[last],
+        p2: path[last - 1],
+    }
+    .intersect_rect(to)?;
+    Some(path)
+}
+
+/// Sorts the coordinates, removes the duplicates and adds the middle between every two
+/// neighbors, so paths can also go through the middle of gaps.
+fn with_midpoints(mut coordinates: Vec<f64>) -> Vec<f64> {
+    coordinates.sort_by(f64::total_cmp);
+    coordinates.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+    let midpoints = coordinates
+        .windows(2)
+        .map(|pair| (pair[0] + pair[1]) / 2.0)
+        .collect::<Vec<_>>();
+    coordinates.extend(midpoints);
+    coordinates.sort_by(f64::total_cmp);
+    coordinates
+}
+
+fn remove_collinear(path: &mut Vec<Point>) {
+    let mut i = 1;
+    while i + 1 < path.len() {
+        let (a, b, c) = (path[i - 1], path[i], path[i + 1]);
+        if (a.x == b.x && b.x == c.x) || (a.y == b.y && b.y == c.y) {
+            path.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Directions of the grid edges: right, left, down, up.
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// A node of the grid with the direction of the edge the path reached it by.
+type Step = ((usize, usize), usize);
+
+struct Grid<'a> {
+    xs: &'a [f64],
+    ys: &'a [f64],
+    obstacles: &'a [Rect],
+    // the rects the path starts and ends in, it may only cross them on their center lines
+    ends: [&'a Rect; 2],
+    bounds: Option<Rect>,
+}
+
+impl Grid<'_> {
+    fn node(&self, point: Point) -> Option<(usize, usize)> {
+        let x = self.xs.iter().position(|x| (x - point.x).abs() < 1e-6)?;
+        let y = self.ys.iter().position(|y| (y - point.y).abs() < 1e-6)?;
+        Some((x, y))
+    }
+
+    fn point(&self, (x, y): (usize, usize)) -> Point {
+        Point::new(self.xs[x], self.ys[y])
+    }
+
+    fn neighbor(&self, (x, y): (usize, usize), direction: usize) -> Option<(usize, usize)> {
+        let (dx, dy) = DIRECTIONS[direction];
+        let x = x.checked_add_signed(dx).filter(|x| *x < self.xs.len())?;
+        let y = y.checked_add_signed(dy).filter(|y| *y < self.ys.len())?;
+        Some((x, y))
+    }
+
+    fn is_free(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        let middle = self.point(a).midpoint(self.point(b));
+        let inside = |rect: &Rect| {
+            middle.x > rect.x0 && middle.x < rect.x1 && middle.y > rect.y0 && middle.y < rect.y1
+        };
+        // running along the bounds looks like a part of them
+        if self.obstacles.iter().any(inside) || !self.bounds.as_ref().is_none_or(inside) {
+            return false;
+        }
+        self.ends.iter().all(|rect| {
+            let center = rect.center();
+            !inside(rect) || middle.x == center.x || middle.y == center.y
+        })
+    }
+
+    /// A* over (node, direction of the last edge), so bends can be counted. Only the states
+    /// it reaches are stored, the whole grid has millions of them in big documents.
+    fn search(&self, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let mut cost: HashMap<Step, f64> = HashMap::new();
+        let mut prev: HashMap<Step, Step> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        let goal_point = self.point(goal);
+        let heuristic = |node| {
+            let point = self.point(node);
+            (point.x - goal_point.x).abs() + (point.y - goal_point.y).abs()
+        };
+
+        for direction in 0..DIRECTIONS.len() {
+            cost.insert((start, direction), 0.0);
+            heap.push(State {
+                node: start,
+                direction,
+                cost: 0.0,
+                estimate: heuristic(start),
+            });
+        }
+
+        while let Some(State {
+            node,
+            direction,
+            cost: current,
+            ..
+        }) = heap.pop()
+        {
+            if node == goal {
+                let mut path = vec![node];
+                let mut state = (node, direction);
+                while let Some(previous) = prev.get(&state) {
+                    path.push(previous.0);
+                    state = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if current > cost[&(node, direction)] {
+                continue;
+            }
+
+            for next_direction in 0..DIRECTIONS.len() {
+                // going back the same way never helps
+                if node != start && next_direction == direction ^ 1 {
+                    continue;
+                }
+                let Some(next) = self.neighbor(node, next_direction) else {
+                    continue;
+                };
+                if !self.is_free(node, next) {
+                    continue;
+                }
+                let bend = if node != start && next_direction != direction {
+                    BEND_COST
+                } else {
+                    0.0
+                };
+                let next_cost = current + self.point(node).distance(self.point(next)) + bend;
+                let known = cost.get(&(next, next_direction)).copied();
+                if known.is_none_or(|known| next_cost < known) {
+                    cost.insert((next, next_direction), next_cost);
+                    prev.insert((next, next_direction), (node, direction));
+                    heap.push(State {
+                        node: next,
+                        direction: next_direction,
+                        cost: next_cost,
+                        estimate: next_cost + heuristic(next),
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    node: (usize, usize),
+    direction: usize,
+    cost: f64,
+    estimate: f64,
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a min-heap on the estimate
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_around_obstacle() {
+        let from = Rect::new(0., 0., 40., 20.);
+        let to = Rect::new(200., 0., 240., 20.);
+        let obstacle = Rect::new(100., -30., 140., 50.);
+        let path = compute_orthogonal_path(&[obstacle], &from, &to, 5., None).unwrap();
+
+        // leaving from the bottom and entering from the bottom needs only 2 bends
+        assert_eq!(path[0], Point::new(20., 20.));
+        assert_eq!(path[path.len() - 1], Point::new(220., 20.));
+        let expanded = obstacle.inflate(5., 5.);
+        for segment in path.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            assert!(a.x == b.x || a.y == b.y);
+            let line = Rect::from_points(a, b);
+            assert_eq!(line.intersect(expanded).area(), 0.);
+        }
+        assert_eq!(path.len(), 4);
+    }
+
+    #[test]
+    fn straight_when_free() {
+        let from = Rect::new(0., 0., 40., 20.);
+        let to = Rect::new(0., 100., 40., 120.);
+        let path = compute_orthogonal_path(&[], &from, &to, 5., None).unwrap();
+        assert_eq!(path, [Point::new(20., 20.), Point::new(20., 100.)]);
+    }
+}
@@ -0,0 +1,1049 @@
+use std::{collections::HashMap, iter, ops::Range};
+
+use kurbo::{Point, Rect, Vec2};
+
+use super::{
+    astar, blocks, encloses, index::ObstacleIndex, lanes, orthogonal, stub_line, waypoint::place,
+    Edge, Segment, HOP_RADIUS,
+};
+use crate::{
+    cell::CellId,
+    configuration::arrow::{Anchor, Arrow, Routing},
+};
+
+/// Routes arrows between the cells of one layout and remembers the results, everything is
+/// thrown away when the layout changes.
+///
+/// The visibility graph between the obstacles is kept for every margin and shared by the
+/// arrows, so it contains the corners of their ends too, which `compute_path` leaves out.
+///
+/// `positions` are the rects of the titles, which arrows are attached to by default, `boxes`
+/// are the whole cells. An arrow attached to a whole cell doesn't go through it, the titles
+/// inside of it are not in its way. An arrow between a cell and one inside of its box goes
+/// from the inner cell to the border of the box, without leaving it.
+pub struct Router {
+    positions: HashMap<CellId, Rect>,
+    boxes: HashMap<CellId, Rect>,
+    index: ObstacleIndex,
+    graphs: HashMap<u64, Graph>,
+    routes: HashMap<RouteKey, Route>,
+    // the last routes of `route_all` with their routing and bundles, before and after they
+    // were put together, the routing decides which of them hop and get bundled
+    apart: Vec<(Option<Route>, Routing, Option<usize>)>,
+    together: Vec<Option<Route>>,
+}
+
+/// Distance between the parallel lanes of the routes that share a part.
+const LANE_SPACING: f64 = 6.0;
+
+// the routes only depend on the rects of the ends while the other cells stay in place
+#[derive(PartialEq, Eq, Hash)]
+struct RouteKey {
+    from: [u64; 4],
+    to: [u64; 4],
+    anchors: [Anchor; 2],
+    waypoints: Vec<[u64; 2]>,
+    margin: u64,
+    routing: Routing,
+}
+
+/// One end of an arrow, as the router sees it.
+struct End {
+    /// The rect the route starts or ends at the border of, for a side anchor the point
+    /// `margin` out of the side.
+    rect: Rect,
+    /// The middle of the side for a side anchor.
+    side: Option<Point>,
+    /// The whole cell when the arrow is attached to it, the route stays out of it.
+    wall: Option<Rect>,
+    /// The obstacles the route may cross: the title of the end and the titles inside of
+    /// the wall.
+    ignored: Vec<usize>,
+}
+
+impl End {
+    fn point(point: Point) -> Self {
+        Self {
+            rect: Rect::from_points(point, point),
+            side: None,
+            wall: None,
+            ignored: vec![],
+        }
+    }
+
+    // where the line from the center of the rect to `other` leaves it
+    fn border(&self, other: Point) -> Option<Point> {
+        let center = self.rect.center();
+        if self.rect.area() == 0.0 {
+            return Some(center);
+        }
+        Segment {
+            p1: center,
+            p2: other,
+        }
+        .intersect_rect(&self.rect)
+    }
+}
+
+impl Router {
+    pub fn new(positions: HashMap<CellId, Rect>, boxes: HashMap<CellId, Rect>) -> Self {
+        Self {
+            index: ObstacleIndex::new(&positions),
+            positions,
+            boxes,
+            graphs: HashMap::new(),
+            routes: HashMap::new(),
+            apart: vec![],
+            together: vec![],
+        }
+    }
+
+    pub fn positions(&self) -> &HashMap<CellId, Rect> {
+        &self.positions
+    }
+
+    pub fn boxes(&self) -> &HashMap<CellId, Rect> {
+        &self.boxes
+    }
+
+    /// Replaces the layout, the cached graphs and routes are kept if it's the same.
+    pub fn set_positions(
+        &mut self,
+        positions: HashMap<CellId, Rect>,
+        boxes: HashMap<CellId, Rect>,
+    ) {
+        if positions != self.positions || boxes != self.boxes {
+            *self = Self::new(positions, boxes);
+        }
+    }
+
+    /// Routes the arrow between the borders of its ends, `arrow.padding` away from all the
+    /// other cells. Returns `None` when one of the ends is not in the layout.
+    pub fn route(&mut self, arrow: &Arrow, routing: Routing) -> Option<Route> {
+        let margin = arrow.padding;
+        let from = self.end(&arrow.from, arrow.from_anchor, margin)?;
+        let to = self.end(&arrow.to, arrow.to_anchor, margin)?;
+        let waypoints = arrow
+            .waypoints
+            .iter()
+            .filter_map(|waypoint| place(waypoint, &self.boxes))
+            .collect::<Vec<_>>();
+        let key = RouteKey {
+            from: rect_bits(from.rect),
+            to: rect_bits(to.rect),
+            anchors: [arrow.from_anchor, arrow.to_anchor],
+            waypoints: waypoints
+                .iter()
+                .map(|point| [point.x, point.y].map(f64::to_bits))
+                .collect(),
+            margin: margin.to_bits(),
+            routing,
+        };
+        if let Some(route) = self.routes.get(&key) {
+            return Some(route.clone());
+        }
+
+        let route = self.compute(arrow, from, to, &waypoints, routing);
+        self.routes.insert(key, route.clone());
+        Some(route)
+    }
+
+    /// Routes all of the arrows like `route`, then moves the parts that they share into
+    /// separate lanes, and finds where each straight arrow crosses the ones before it, it
+    /// hops over them there. Curved arrows don't hop and aren't hopped over, their lines
+    /// are not where their points are.
+    ///
+    /// Arrows with the same bundle (e.g. the index of their layer) that go between the cells
+    /// of the same two outer cells share one route between those, see `bundle`.
+    pub fn route_all(&mut self, arrows: &[(&Arrow, Routing, Option<usize>)]) -> Vec<Option<Route>> {
+        let mut routes = arrows
+            .iter()
+            .map(|(arrow, routing, _)| self.route(arrow, *routing))
+            .collect::<Vec<_>>();
+        let apart = routes
+            .iter()
+            .zip(arrows)
+            .map(|(route, (_, routing, bundle))| (route.clone(), *routing, *bundle))
+            .collect::<Vec<_>>();
+        if apart == self.apart {
+            return self.together.clone();
+        }
+
+        let keys = self.bundle(arrows, &mut routes);
+        let mut points = routes
+            .iter()
+            .map(|route| route.as_ref().map_or(vec![], |route| route.points.clone()))
+            .collect::<Vec<_>>();
+        lanes::separate(&mut points, &keys, LANE_SPACING);
+        let straight = (0..arrows.len())
+            .filter(|i| arrows[*i].1 != Routing::Curved && routes[*i].is_some())
+            .collect::<Vec<_>>();
+        let straight_points = straight
+            .iter()
+            .map(|i| points[*i].as_slice())
+            .collect::<Vec<_>>();
+        let straight_keys = straight.iter().map(|i| keys[*i]).collect::<Vec<_>>();
+        let mut hops = vec![vec![]; arrows.len()];
+        for (i, crossings) in straight.iter().zip(lanes::crossings(
+            &straight_points,
+            &straight_keys,
+            3. * HOP_RADIUS,
+        )) {
+            hops[*i] = crossings;
+        }
+
+        let together = routes
+            .iter()
+            .zip(points.into_iter().zip(hops))
+            .map(|(route, (points, hops))| {
+                route.as_ref().map(|route| Route {
+                    points,
+                    hops,
+                    ..route.clone()
+                })
+            })
+            .collect::<Vec<_>>();
+        self.apart = apart;
+        self.together = together.clone();
+        together
+    }
+
+    // the arrows of a bundle between the cells inside of two outer cells that are apart are
+    // routed between those once, the trunk, and every arrow only goes from its ends to the
+    // trunk inside of them. Curved arrows and arrows with waypoints are left alone.
+    //
+    // returns the lane of every route, the routes of a bundle stay together in one
+    fn bundle(
+        &mut self,
+        arrows: &[(&Arrow, Routing, Option<usize>)],
+        routes: &mut [Option<Route>],
+    ) -> Vec<usize> {
+        let mut keys = (0..arrows.len()).collect::<Vec<_>>();
+        // the arrows between the same outer cells in either direction, the reversed ones
+        // are marked
+        let mut groups = HashMap::<_, Vec<(usize, bool)>>::new();
+        for (i, (arrow, routing, bundle)) in arrows.iter().enumerate() {
+            let Some(bundle) = bundle else {
+                continue;
+            };
+            if *routing == Routing::Curved || !arrow.waypoints.is_empty() || routes[i].is_none() {
+                continue;
+            }
+            let (Some((from, from_box)), Some((to, to_box))) =
+                (self.parent(&arrow.from), self.parent(&arrow.to))
+            else {
+                continue;
+            };
+            if from_box.intersect(to_box).area() > 0.0 {
+                continue;
+            }
+            let reversed = (*bundle, to.clone(), from.clone(), *routing);
+            if let Some(group) = groups.get_mut(&reversed) {
+                group.push((i, true));
+            } else {
+                groups
+                    .entry((*bundle, from, to, *routing))
+                    .or_default()
+                    .push((i, false));
+            }
+        }
+
+        for ((_, from, to, routing), members) in groups {
+            if members.len() < 2 {
+                continue;
+            }
+            let margin = members
+                .iter()
+                .map(|(i, _)| arrows[*i].0.padding)
+                .fold(0.0, f64::max);
+            let margins = [margin, margin / 2.0, margin / 4.0];
+            let (Some(start), Some(end)) = (
+                self.end(&from, Anchor::Cell, margin),
+                self.end(&to, Anchor::Cell, margin),
+            ) else {
+                continue;
+            };
+            let trunk = self.compute_leg(&start, &end, margins, routing);
+            if trunk.unroutable {
+                continue;
+            }
+
+            let (first, last) = (trunk.points[0], trunk.points[trunk.points.len() - 1]);
+            for (n, (i, reversed)) in members.iter().enumerate() {
+                let arrow = arrows[*i].0;
+                let margin = arrow.padding;
+                let margins = [margin, margin / 2.0, margin / 4.0];
+                let (Some(mut a), Some(mut b)) = (
+                    self.end(&arrow.from, arrow.from_anchor, margin),
+                    self.end(&arrow.to, arrow.to_anchor, margin),
+                ) else {
+                    continue;
+                };
+                if *reversed {
+                    (a, b) = (b, a);
+                }
+                let branch = self.compute_inside(&a, start.rect, first, margins, routing);
+                let mut other = self.compute_inside(&b, end.rect, last, margins, routing);
+                other.points.reverse();
+
+                let mut points = branch.points;
+                let range = points.len() - 1..points.len() - 1 + trunk.points.len();
+                points.extend(&trunk.points[1..]);
+                points.extend(other.points.into_iter().skip(1));
+                let range = if *reversed {
+                    points.reverse();
+                    points.len() - range.end..points.len() - range.start
+                } else {
+                    range
+                };
+                routes[*i] = Some(Route {
+                    points,
+                    unroutable: branch.unroutable || other.unroutable,
+                    hops: vec![],
+                    trunk: Some(Trunk {
+                        range,
+                        arrows: members.len(),
+                        first: n == 0,
+                    }),
+                });
+                keys[*i] = members[0].0;
+            }
+        }
+        keys
+    }
+
+    // the smallest cell around the cell, e.g. the one with the table it's in
+    fn parent(&self, id: &CellId) -> Option<(CellId, Rect)> {
+        let cell = self.boxes.get(id).or(self.positions.get(id))?;
+        self.boxes
+            .iter()
+            .filter(|(other, rect)| *other != id && encloses_rect(rect, cell))
+            .min_by(|a, b| a.1.area().total_cmp(&b.1.area()))
+            .map(|(other, rect)| (other.clone(), *rect))
+    }
+
+    fn end(&self, id: &CellId, anchor: Anchor, margin: f64) -> Option<End> {
+        let title = *self.positions.get(id)?;
+        let mut ignored = self.index.index_of(id).into_iter().collect::<Vec<_>>();
+        if anchor == Anchor::Title {
+            return Some(End {
+                rect: title,
+                side: None,
+                wall: None,
+                ignored,
+            });
+        }
+
+        let cell = self.boxes.get(id).copied().unwrap_or(title);
+        ignored.extend(
+            self.index
+                .near(cell)
+                .filter(|i| encloses_rect(&cell, &self.index.obstacles()[*i].1)),
+        );
+        let (rect, side) = match side(anchor, &cell) {
+            Some((middle, normal)) => {
+                let port = middle + normal * margin;
+                (Rect::from_points(port, port), Some(middle))
+            }
+            None => (cell, None),
+        };
+        Some(End {
+            rect,
+            side,
+            wall: Some(cell),
+            ignored,
+        })
+    }
+
+    // the waypoints of an arrow to a cell around it are left out, it always goes straight
+    // to the border of the outer cell
+    //
+    // when there is no way with the whole margin, smaller ones are tried, and when there
+    // is no way at all (e.g. the ends are enclosed or overlap), the ends are connected
+    // by a straight line
+    fn compute(
+        &mut self,
+        arrow: &Arrow,
+        from: End,
+        to: End,
+        waypoints: &[Point],
+        routing: Routing,
+    ) -> Route {
+        let margin = arrow.padding;
+        let margins = [margin, margin / 2.0, margin / 4.0];
+        let (from_title, to_title) = (self.positions[&arrow.from], self.positions[&arrow.to]);
+        if let Some(container) = self
+            .boxes
+            .get(&arrow.from)
+            .filter(|b| encloses_rect(b, &to_title))
+        {
+            let container = *container;
+            let mut route =
+                self.compute_nested(&to, container, arrow.from_anchor, margins, routing);
+            route.points.reverse();
+            return route;
+        }
+        if let Some(container) = self
+            .boxes
+            .get(&arrow.to)
+            .filter(|b| encloses_rect(b, &from_title))
+        {
+            let container = *container;
+            return self.compute_nested(&from, container, arrow.to_anchor, margins, routing);
+        }
+        if waypoints.is_empty() {
+            return self.compute_leg(&from, &to, margins, routing);
+        }
+
+        // every part between two stops is routed on its own
+        let stops = waypoints.iter().map(|point| End::point(*point));
+        let ends = iter::once(from)
+            .chain(stops)
+            .chain(iter::once(to))
+            .collect::<Vec<_>>();
+        let mut route = Route::found(vec![]);
+        for pair in ends.windows(2) {
+            let leg = self.compute_leg(&pair[0], &pair[1], margins, routing);
+            let skip = usize::from(!route.points.is_empty());
+            route.points.extend(leg.points.into_iter().skip(skip));
+            route.unroutable |= leg.unroutable;
+        }
+        route
+    }
+
+    fn compute_leg(&mut self, from: &End, to: &End, margins: [f64; 3], routing: Routing) -> Route {
+        let with_ports = |points| with_ports(points, from.side, to.side);
+        if from.rect.intersect(to.rect).area() > 0.0 {
+            return Route::unroutable(with_ports(vec![from.rect.center(), to.rect.center()]));
+        }
+
+        let ignored = [from.ignored.as_slice(), to.ignored.as_slice()].concat();
+        if routing == Routing::Orthogonal {
+            // the walls of side anchors are not ends of the path, they are in its way
+            let rest_rects = self
+                .index
+                .obstacles()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !ignored.contains(i))
+                .map(|(_, (_, rect))| *rect)
+                .chain(
+                    [from, to]
+                        .into_iter()
+                        .filter_map(|end| end.side.and(end.wall)),
+                )
+                .collect::<Vec<_>>();
+            // cells that overlap their padding may leave no orthogonal way out,
+            // a direct path is better than none
+            let path = margins.into_iter().find_map(|margin| {
+                orthogonal::compute_orthogonal_path(&rest_rects, &from.rect, &to.rect, margin, None)
+            });
+            if let Some(points) = path {
+                return Route::found(with_ports(points));
+            }
+        }
+
+        let center_segment = Segment {
+            p1: from.rect.center(),
+            p2: to.rect.center(),
+        };
+        let (Some(start), Some(goal)) =
+            (from.border(center_segment.p2), to.border(center_segment.p1))
+        else {
+            return Route::unroutable(with_ports(vec![center_segment.p1, center_segment.p2]));
+        };
+
+        let walls = from.wall.into_iter().chain(to.wall).collect::<Vec<_>>();
+        for margin in margins {
+            let index = &self.index;
+            let graph = self
+                .graphs
+                .entry(margin.to_bits())
+                .or_insert_with(|| Graph::new(index, margin));
+            if let Some(points) = graph.route(index, start, goal, &ignored, &walls, margin, None) {
+                return Route::found(with_ports(points));
+            }
+        }
+        Route::unroutable(with_ports(vec![start, goal]))
+    }
+
+    // a route from the `inner` end to the side of the `container` it's in that is chosen by
+    // the anchor of the container, or the closest one
+    fn compute_nested(
+        &mut self,
+        inner: &End,
+        container: Rect,
+        anchor: Anchor,
+        margins: [f64; 3],
+        routing: Routing,
+    ) -> Route {
+        let anchor = match side(anchor, &container) {
+            Some((middle, _)) => middle,
+            None => stub_line(&inner.rect, &container).1,
+        };
+        self.compute_inside(inner, container, anchor, margins, routing)
+    }
+
+    // a route from the `inner` end to the `anchor` on the border of the `container` it's in,
+    // without leaving it
+    fn compute_inside(
+        &mut self,
+        inner: &End,
+        container: Rect,
+        anchor: Point,
+        margins: [f64; 3],
+        routing: Routing,
+    ) -> Route {
+        let start = inner.border(anchor).unwrap_or(inner.rect.center());
+        let with_ports = |points| with_ports(points, inner.side, None);
+
+        if routing == Routing::Orthogonal {
+            let rest_rects = self
+                .index
+                .obstacles()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !inner.ignored.contains(i))
+                .map(|(_, (_, rect))| *rect)
+                .chain(inner.side.and(inner.wall))
+                .collect::<Vec<_>>();
+            let anchor_rect = Rect::from_points(anchor, anchor);
+            let path = margins.into_iter().find_map(|margin| {
+                orthogonal::compute_orthogonal_path(
+                    &rest_rects,
+                    &inner.rect,
+                    &anchor_rect,
+                    margin,
+                    Some(container),
+                )
+            });
+            if let Some(points) = path {
+                return Route::found(with_ports(points));
+            }
+        }
+
+        let walls = inner.wall.into_iter().collect::<Vec<_>>();
+        for margin in margins {
+            let index = &self.index;
+            let graph = self
+                .graphs
+                .entry(margin.to_bits())
+                .or_insert_with(|| Graph::new(index, margin));
+            let path = graph.route(
+                index,
+                start,
+                anchor,
+                &inner.ignored,
+                &walls,
+                margin,
+                Some(container),
+            );
+            if let Some(points) = path {
+                return Route::found(with_ports(points));
+            }
+        }
+        Route::unroutable(with_ports(vec![start, anchor]))
+    }
+}
+
+// the middle of the side of the rect an anchor points at, and the direction out of it
+fn side(anchor: Anchor, rect: &Rect) -> Option<(Point, Vec2)> {
+    let center = rect.center();
+    match anchor {
+        Anchor::Title | Anchor::Cell => None,
+        Anchor::Top => Some((Point::new(center.x, rect.y0), Vec2::new(0.0, -1.0))),
+        Anchor::Bottom => Some((Point::new(center.x, rect.y1), Vec2::new(0.0, 1.0))),
+        Anchor::Left => Some((Point::new(rect.x0, center.y), Vec2::new(-1.0, 0.0))),
+        Anchor::Right => Some((Point::new(rect.x1, center.y), Vec2::new(1.0, 0.0))),
+    }
+}
+
+// routes of side anchors end out of the side, the arrow leaves it straight
+fn with_ports(mut points: Vec<Point>, from: Option<Point>, to: Option<Point>) -> Vec<Point> {
+    points.splice(0..0, from);
+    points.extend(to);
+    points
+}
+
+// whether the line goes through the inside of the rect, running along its border is fine
+fn crosses(rect: &Rect, p0: Point, p1: Point) -> bool {
+    let inside = rect.inset(-0.5);
+    inside.contains(p0) || Segment { p1: p0, p2: p1 }.intersect_rect(&inside).is_some()
+}
+
+fn encloses_rect(outer: &Rect, inner: &Rect) -> bool {
+    encloses(outer, inner.origin()) && encloses(outer, Point::new(inner.x1, inner.y1))
+}
+
+/// The path of an arrow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+    pub points: Vec<Point>,
+    // no way around the other cells was found, the points are a straight line through them
+    pub unroutable: bool,
+    // where the line jumps over the arrows it crosses, only set by `Router::route_all`
+    pub hops: Vec<Point>,
+    // the part shared with the other arrows of its bundle, only set by `Router::route_all`
+    pub trunk: Option<Trunk>,
+}
+
+/// The part of a route that the arrows of a bundle share.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trunk {
+    /// The points of the route that are on the trunk.
+    pub range: Range<usize>,
+    /// How many arrows go through it.
+    pub arrows: usize,
+    /// Only the first arrow of the bundle paints the trunk.
+    pub first: bool,
+}
+
+impl Route {
+    /// Radius of the mark of an unroutable arrow.
+    pub const MARK_RADIUS: f64 = 7.0;
+
+    fn found(points: Vec<Point>) -> Self {
+        Self {
+            points,
+            unroutable: false,
+            hops: vec![],
+            trunk: None,
+        }
+    }
+
+    fn unroutable(points: Vec<Point>) -> Self {
+        Self {
+            points,
+            unroutable: true,
+            hops: vec![],
+            trunk: None,
+        }
+    }
+
+    /// Where the mark of an unroutable arrow is drawn.
+    pub fn middle(&self) -> Point {
+        self.points[0].midpoint(self.points[self.points.len() - 1])
+    }
+}
+
+fn rect_bits(rect: Rect) -> [u64; 4] {
+    [rect.x0, rect.y0, rect.x1, rect.y1].map(f64::to_bits)
+}
+
+/// The visibility graph between the corners of all obstacles. The edges of a node are only
+/// found when a route gets to it, most of the nodes are never needed.
+struct Graph {
+    nodes: Vec<Point>,
+    neighbors: Vec<Option<Vec<Neighbor>>>,
+}
+
+struct Neighbor {
+    to: usize,
+    cost: f64,
+    // the obstacles in the way, an arrow can still take the edge when they are its ends,
+    // edges with more of them are left out
+    blockers: [Option<usize>; 2],
+}
+
+impl Graph {
+    fn new(index: &ObstacleIndex, margin: f64) -> Self {
+        // only the corners, the middles of the sides that `compute_path` adds are inside
+        // of the margin of their cell, they never connect to anything
+        let nodes = index
+            .obstacles()
+            .iter()
+            .flat_map(|(_, rect)| corners(rect.inflate(margin, margin)))
+            .collect::<Vec<_>>();
+        Self {
+            neighbors: (0..nodes.len()).map(|_| None).collect(),
+            nodes,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn route(
+        &mut self,
+        index: &ObstacleIndex,
+        start: Point,
+        goal: Point,
+        ignored: &[usize],
+        walls: &[Rect],
+        margin: f64,
+        bounds: Option<Rect>,
+    ) -> Option<Vec<Point>> {
+        let is_ignored = |obstacle| ignored.contains(&obstacle);
+        let outside_walls = |p0, p1| walls.iter().all(|wall| !crosses(wall, p0, p1));
+        let visible =
+            |p0, p1| blockers(index, p0, p1, margin).all(is_ignored) && outside_walls(p0, p1);
+        let in_bounds = |point| bounds.is_none_or(|bounds| encloses(&bounds, point));
+
+        // the start and the goal are at indices 0 and 1, the corners around the walls, which
+        // the graph doesn't have, and then the graph nodes follow them
+        let mut nodes = vec![start, goal];
+        nodes.extend(
+            walls
+                .iter()
+                .flat_map(|wall| corners(wall.inflate(margin, margin))),
+        );
+        let offset = nodes.len();
+        nodes.extend(&self.nodes);
+        let neighbors = |i: usize, edges: &mut Vec<Edge>| {
+            let from = nodes[i];
+            if i < offset {
+                edges.extend(
+                    (0..nodes.len())
+                        .filter(|j| *j != i && in_bounds(nodes[*j]) && visible(from, nodes[*j]))
+                        .map(|j| Edge {
+                            to: j,
+                            cost: from.distance(nodes[j]),
+                        }),
+                );
+                return;
+            }
+            let node = i - offset;
+            let neighbors = self.neighbors[node]
+                .get_or_insert_with(|| find_neighbors(index, &self.nodes, node, margin));
+            edges.extend(
+                neighbors
+                    .iter()
+                    .filter(|neighbor| {
+                        neighbor.blockers.iter().flatten().all(|b| is_ignored(*b))
+                            && in_bounds(self.nodes[neighbor.to])
+                            && outside_walls(from, self.nodes[neighbor.to])
+                    })
+                    .map(|neighbor| Edge {
+                        to: neighbor.to + offset,
+                        cost: neighbor.cost,
+                    }),
+            );
+            edges.extend(
+                (1..offset)
+                    .filter(|j| visible(from, nodes[*j]))
+                    .map(|j| Edge {
+                        to: j,
+                        cost: from.distance(nodes[j]),
+                    }),
+            );
+        };
+        astar(&nodes, neighbors, 0, 1)
+            .map(|path_indices| path_indices.into_iter().map(|i| nodes[i]).collect())
+    }
+}
+
+fn corners(rect: Rect) -> [Point; 4] {
+    [
+        Point::new(rect.x0, rect.y0),
+        Point::new(rect.x1, rect.y0),
+        Point::new(rect.x1, rect.y1),
+        Point::new(rect.x0, rect.y1),
+    ]
+}
+
+fn find_neighbors(
+    index: &ObstacleIndex,
+    nodes: &[Point],
+    node: usize,
+    margin: f64,
+) -> Vec<Neighbor> {
+    let from = nodes[node];
+    (0..nodes.len())
+        .filter(|to| *to != node)
+        .filter_map(|to| {
+            let mut blockers = blockers(index, from, nodes[to], margin);
+            let found = [blockers.next(), blockers.next()];
+            blockers.next().is_none().then(|| Neighbor {
+                to,
+                cost: from.distance(nodes[to]),
+                blockers: found,
+            })
+        })
+        .collect()
+}
+
+// the indices of the obstacles in the way of the line between the points
+fn blockers(
+    index: &ObstacleIndex,
+    p0: Point,
+    p1: Point,
+    margin: f64,
+) -> impl Iterator<Item = usize> + '_ {
+    index
+        .near(Rect::from_points(p0, p1).inflate(margin, margin))
+        .filter(move |i| blocks(&p0, &p1, &index.obstacles()[*i].1, margin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::configuration::arrow::Waypoint;
+
+    fn arrow(from: &CellId, to: &CellId) -> Arrow {
+        Arrow {
+            padding: 5.,
+            ..Arrow::new(from.clone(), to.clone())
+        }
+    }
+
+    #[test]
+    fn routes_around_and_follows_layout() {
+        let (a, b, c) = (CellId::new(), CellId::new(), CellId::new());
+        let mut positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(200., 0., 240., 20.)),
+            (c.clone(), Rect::new(100., -30., 140., 50.)),
+        ]);
+        let mut router = Router::new(positions.clone(), HashMap::new());
+        let route = router.route(&arrow(&a, &b), Routing::Direct).unwrap();
+        // over or under the cell in the middle
+        assert_eq!(route.points.len(), 4);
+        assert_eq!(router.route(&arrow(&a, &b), Routing::Direct), Some(route));
+
+        positions.remove(&c);
+        router.set_positions(positions, HashMap::new());
+        let route = router.route(&arrow(&a, &b), Routing::Direct).unwrap();
+        assert_eq!(route.points, [Point::new(40., 10.), Point::new(200., 10.)]);
+    }
+
+    #[test]
+    fn falls_back_to_straight_line() {
+        let (a, b, c) = (CellId::new(), CellId::new(), CellId::new());
+        // `b` is inside of `c`, every way to it goes through `c`
+        let positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(200., 0., 240., 20.)),
+            (c.clone(), Rect::new(180., -20., 260., 40.)),
+        ]);
+        let mut router = Router::new(positions, HashMap::new());
+        for routing in Routing::ALL {
+            let route = router.route(&arrow(&a, &b), routing).unwrap();
+            assert!(route.unroutable);
+            assert_eq!(route.points, [Point::new(40., 10.), Point::new(200., 10.)]);
+        }
+
+        // the ends overlap
+        let positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(10., 5., 30., 15.)),
+        ]);
+        let route = Router::new(positions, HashMap::new())
+            .route(&arrow(&a, &b), Routing::Direct)
+            .unwrap();
+        assert!(route.unroutable);
+    }
+
+    #[test]
+    fn routes_inside_of_container() {
+        let (outer, inner, other) = (CellId::new(), CellId::new(), CellId::new());
+        let container = Rect::new(0., 0., 200., 100.);
+        let positions = HashMap::from([
+            (outer.clone(), Rect::new(80., 5., 120., 25.)),
+            (inner.clone(), Rect::new(20., 60., 60., 80.)),
+            (other.clone(), Rect::new(130., 60., 170., 80.)),
+        ]);
+        let boxes = HashMap::from([
+            (outer.clone(), container),
+            (inner.clone(), Rect::new(10., 50., 70., 90.)),
+            (other.clone(), Rect::new(120., 50., 180., 90.)),
+        ]);
+        let mut router = Router::new(positions, boxes);
+        for routing in Routing::ALL {
+            let route = router.route(&arrow(&outer, &inner), routing).unwrap();
+            assert!(!route.unroutable);
+            // from the closest side of the container to the inner cell
+            assert_eq!(route.points[0], Point::new(40., 100.));
+            assert_eq!(route.points[route.points.len() - 1], Point::new(40., 80.));
+
+            let back = router.route(&arrow(&inner, &outer), routing).unwrap();
+            assert_eq!(back.points[0], Point::new(40., 80.));
+            assert_eq!(back.points[back.points.len() - 1], Point::new(40., 100.));
+        }
+    }
+
+    #[test]
+    fn anchors_to_whole_cell() {
+        let (outer, inner, target) = (CellId::new(), CellId::new(), CellId::new());
+        let cell = Rect::new(0., 0., 100., 100.);
+        let positions = HashMap::from([
+            (outer.clone(), Rect::new(30., 5., 70., 25.)),
+            // right in the way out of the title of `outer`
+            (inner.clone(), Rect::new(60., 40., 95., 60.)),
+            (target.clone(), Rect::new(300., 40., 340., 60.)),
+        ]);
+        let boxes = HashMap::from([
+            (outer.clone(), cell),
+            (inner.clone(), Rect::new(55., 35., 100., 65.)),
+            (target.clone(), Rect::new(300., 40., 340., 60.)),
+        ]);
+        let mut router = Router::new(positions, boxes);
+
+        let mut to_target = arrow(&outer, &target);
+        to_target.from_anchor = Anchor::Cell;
+        let route = router.route(&to_target, Routing::Direct).unwrap();
+        // straight out of the border of the cell, the title inside of it is not in the way
+        assert_eq!(route.points, [Point::new(100., 50.), Point::new(300., 50.)]);
+
+        to_target.from_anchor = Anchor::Top;
+        for routing in Routing::ALL {
+            let route = router.route(&to_target, routing).unwrap();
+            assert!(!route.unroutable);
+            assert_eq!(
+                route.points[..2],
+                [Point::new(50., 0.), Point::new(50., -5.)]
+            );
+            // around the cell, not through it
+            for segment in route.points[1..].windows(2) {
+                assert!(!crosses(&cell, segment[0], segment[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn goes_through_waypoints() {
+        let (a, b, root) = (CellId::new(), CellId::new(), CellId::new());
+        let positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(200., 0., 240., 20.)),
+        ]);
+        let boxes = HashMap::from([(root.clone(), Rect::new(-50., -50., 250., 250.))]);
+        let mut router = Router::new(positions, boxes);
+        let mut arrow = arrow(&a, &b);
+        arrow.waypoints = vec![Waypoint {
+            cell: root,
+            x: 0.5,
+            y: 0.5,
+        }];
+        for routing in Routing::ALL {
+            let route = router.route(&arrow, routing).unwrap();
+            assert!(!route.unroutable);
+            assert!(route.points.contains(&Point::new(100., 100.)));
+            // on the borders of the ends
+            let on_border = |rect: Rect, point| {
+                rect.inflate(0.5, 0.5).contains(point) && !rect.inset(-0.5).contains(point)
+            };
+            assert!(on_border(Rect::new(0., 0., 40., 20.), route.points[0]));
+            let last = route.points[route.points.len() - 1];
+            assert!(on_border(Rect::new(200., 0., 240., 20.), last));
+        }
+    }
+
+    #[test]
+    fn puts_routes_together() {
+        let (a, b, c, d) = (CellId::new(), CellId::new(), CellId::new(), CellId::new());
+        let positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(200., 0., 240., 20.)),
+            (c.clone(), Rect::new(100., -100., 140., -80.)),
+            (d.clone(), Rect::new(100., 100., 140., 120.)),
+        ]);
+        let mut router = Router::new(positions, HashMap::new());
+        let (first, second, across) = (arrow(&a, &b), arrow(&a, &b), arrow(&c, &d));
+        let arrows = [
+            (&first, Routing::Direct, None),
+            (&second, Routing::Direct, None),
+            (&across, Routing::Direct, None),
+        ];
+        let routes = router.route_all(&arrows);
+
+        // the arrows between the same cells are next to each other
+        let (first, second) = (routes[0].as_ref().unwrap(), routes[1].as_ref().unwrap());
+        assert_eq!(first.points[0], Point::new(40., 7.));
+        assert_eq!(second.points[0], Point::new(40., 13.));
+        // the last arrow hops over both of them
+        let across = routes[2].as_ref().unwrap();
+        assert_eq!(across.hops.len(), 2);
+        for (hop, expected) in across
+            .hops
+            .iter()
+            .zip([Point::new(120., 7.), Point::new(120., 13.)])
+        {
+            assert!(hop.distance(expected) < 1e-9);
+        }
+        assert!(first.hops.is_empty());
+        assert_eq!(router.route_all(&arrows), routes);
+    }
+
+    #[test]
+    fn changing_routing_updates_hops() {
+        let (a, b, c, d) = (CellId::new(), CellId::new(), CellId::new(), CellId::new());
+        let positions = HashMap::from([
+            (a.clone(), Rect::new(0., 0., 40., 20.)),
+            (b.clone(), Rect::new(200., 0., 240., 20.)),
+            (c.clone(), Rect::new(100., -100., 140., -80.)),
+            (d.clone(), Rect::new(100., 100., 140., 120.)),
+        ]);
+        let mut router = Router::new(positions, HashMap::new());
+        let (along, across) = (arrow(&a, &b), arrow(&c, &d));
+        let mut hops = |routings: [Routing; 2]| {
+            let arrows = [(&along, routings[0], None), (&across, routings[1], None)];
+            router.route_all(&arrows)[1].as_ref().unwrap().hops.len()
+        };
+
+        assert_eq!(hops([Routing::Direct, Routing::Direct]), 1);
+        // both routes keep their points, but curved arrows don't hop
+        assert_eq!(hops([Routing::Direct, Routing::Curved]), 0);
+        assert_eq!(hops([Routing::Direct, Routing::Direct]), 1);
+        // and aren't hopped over
+        assert_eq!(hops([Routing::Curved, Routing::Direct]), 0);
+    }
+
+    #[test]
+    fn bundles_arrows_between_tables() {
+        let (p, q) = (CellId::new(), CellId::new());
+        let (a1, a2, b1, b2) = (CellId::new(), CellId::new(), CellId::new(), CellId::new());
+        let positions = HashMap::from([
+            (p.clone(), Rect::new(0., 0., 100., 20.)),
+            (q.clone(), Rect::new(300., 0., 400., 20.)),
+            (a1.clone(), Rect::new(10., 30., 90., 50.)),
+            (a2.clone(), Rect::new(10., 70., 90., 90.)),
+            (b1.clone(), Rect::new(310., 30., 390., 50.)),
+            (b2.clone(), Rect::new(310., 70., 390., 90.)),
+        ]);
+        let boxes = HashMap::from([
+            (p.clone(), Rect::new(0., 0., 100., 100.)),
+            (q.clone(), Rect::new(300., 0., 400., 100.)),
+        ]);
+        let mut router = Router::new(positions.clone(), boxes);
+        let arrows = [arrow(&a1, &b1), arrow(&a2, &b2), arrow(&b2, &a1)];
+        for routing in [Routing::Direct, Routing::Orthogonal] {
+            let routed = arrows
+                .iter()
+                .map(|arrow| (arrow, routing, Some(0)))
+                .collect::<Vec<_>>();
+            let routes = router.route_all(&routed);
+
+            let trunks = routes
+                .iter()
+                .map(|route| {
+                    let route = route.as_ref().unwrap();
+                    let trunk = route.trunk.as_ref().unwrap();
+                    assert_eq!(trunk.arrows, 3);
+                    (route.points[trunk.range.clone()].to_vec(), trunk.first)
+                })
+                .collect::<Vec<_>>();
+            // the trunk goes between the tables, the reversed arrow goes through it backwards
+            let trunk = &trunks[0].0;
+            assert!(trunk[0].x >= 100. && trunk[trunk.len() - 1].x <= 300.);
+            assert_eq!(&trunks[1].0, trunk);
+            assert!(trunks[2].0.iter().rev().eq(trunk));
+            assert_eq!(trunks.iter().filter(|(_, first)| *first).count(), 1);
+            // and the arrows split up to their own cells
+            for (arrow, route) in arrows.iter().zip(&routes) {
+                let points = &route.as_ref().unwrap().points;
+                assert!(positions[&arrow.from].inflate(0.5, 0.5).contains(points[0]));
+                let last = points[points.len() - 1];
+                assert!(positions[&arrow.to].inflate(0.5, 0.5).contains(last));
+            }
+        }
+
+        // without a bundle, every arrow has its own route
+        let routed = arrows
+            .iter()
+            .map(|arrow| (arrow, Routing::Direct, None))
+            .collect::<Vec<_>>();
+        let routes = router.route_all(&routed);
+        assert!(routes
+            .iter()
+            .all(|route| route.as_ref().unwrap().trunk.is_none()));
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use kurbo::{Point, Rect};
+
+use crate::{cell::CellId, configuration::arrow::Waypoint};
+
+/// The waypoint at `point`, relative to the smallest of the `boxes` it's in, so it stays in
+/// the same place of the innermost cell. `None` when it's outside of all of them.
+pub fn pin(point: Point, boxes: &HashMap<CellId, Rect>) -> Option<Waypoint> {
+    let (cell, rect) = boxes
+        .iter()
+        .filter(|(_, rect)| rect.contains(point) && rect.area() > 0.0)
+        .min_by(|a, b| a.1.area().total_cmp(&b.1.area()))?;
+    Some(Waypoint {
+        cell: cell.clone(),
+        x: (point.x - rect.x0) / rect.width(),
+        y: (point.y - rect.y0) / rect.height(),
+    })
+}
+
+/// Where the waypoint is in the current layout, `None` when its cell is not in it.
+pub fn place(waypoint: &Waypoint, boxes: &HashMap<CellId, Rect>) -> Option<Point> {
+    let rect = boxes.get(&waypoint.cell)?;
+    Some(Point::new(
+        rect.x0 + waypoint.x * rect.width(),
+        rect.y0 + waypoint.y * rect.height(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_innermost_cell() {
+        let (outer, inner) = (CellId::new(), CellId::new());
+        let mut boxes = HashMap::from([
+            (outer.clone(), Rect::new(0., 0., 200., 200.)),
+            (inner.clone(), Rect::new(100., 100., 150., 150.)),
+        ]);
+        let waypoint = pin(Point::new(110., 140.), &boxes).unwrap();
+        assert_eq!(waypoint.cell, inner);
+        assert_eq!(place(&waypoint, &boxes), Some(Point::new(110., 140.)));
+
+        // the inner cell grows and moves
+        boxes.insert(inner.clone(), Rect::new(50., 50., 150., 150.));
+        assert_eq!(place(&waypoint, &boxes), Some(Point::new(70., 130.)));
+
+        assert_eq!(pin(Point::new(300., 0.), &boxes), None);
+        boxes.remove(&inner);
+        assert_eq!(place(&waypoint, &boxes), None);
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cell::Cell;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Table {
+    pub cells: Cells,
+}
+
+impl Table {
+    pub fn new(hierarchy_depth: usize) -> Self {
+        Self {
+            cells: Cells::new(hierarchy_depth),
+        }
+    }
+}
+
+pub type RowType = Vec<Cell>;
+pub type RowsType = Vec<RowType>;
+
+// a grid of cells, it always has at least one row and one column
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Cells {
+    pub data: RowsType,
+    hierarchy_depth: usize,
+}
+
+impl Cells {
+    pub fn new(hierarchy_depth: usize) -> Self {
+        Self {
+            data: vec![vec![Cell::new(None, hierarchy_depth)]],
+            hierarchy_depth,
+        }
+    }
+
+    pub fn hierarchy_depth(&self) -> usize {
+        self.hierarchy_depth
+    }
+
+    pub(crate) fn set_hierarchy_depth(&mut self, hierarchy_depth: usize) {
+        self.hierarchy_depth = hierarchy_depth;
+    }
+
+    // restores the shape of a grid edited by hand: pads the rows to the longest one, with
+    // one cell at least, returns whether anything was missing
+    pub(crate) fn fill(&mut self) -> bool {
+        if self.data.is_empty() {
+            self.data.push(vec![]);
+        }
+        let cols = self.data.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let mut filled = false;
+        for row in &mut self.data {
+            while row.len() < cols {
+                row.push(Cell::new(None, self.hierarchy_depth));
+                filled = true;
+            }
+        }
+        filled
+    }
+
+    pub fn add_row(&mut self, index: usize) {
+        let cols = self.cols();
+        self.data.insert(
+            index,
+            (0..cols)
+                .map(|_| Cell::new(None, self.hierarchy_depth))
+                .collect(),
+        );
+    }
+
+    pub fn remove_row(&mut self, index: usize) -> RowType {
+        self.data.remove(index)
+    }
+
+    pub fn add_col(&mut self, index: usize) {
+        for row in self.data.iter_mut() {
+            row.insert(index, Cell::new(None, self.hierarchy_depth));
+        }
+    }
+
+    pub fn remove_col(&mut self, index: usize) -> Vec<Cell> {
+        self.data.iter_mut().map(|row| row.remove(index)).collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.data[0].len()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.data.get(row)?.get(col)
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.data.get_mut(row)?.get_mut(col)
+    }
+}
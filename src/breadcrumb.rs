@@ -0,0 +1,68 @@
+use floem::{kurbo::Stroke, prelude::*};
+use graphyr_core::{cell::CellPos, Data};
+
+use crate::{
+    theme::MyTheme,
+    view_data::{displayed_cell, ViewData},
+};
+
+// a strip showing the path from the highest cell to the displayed one,
+// every part can be clicked to jump there
+pub fn breadcrumb(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    my_theme: MyTheme,
+) -> DynamicContainer<CellPos> {
+    let displayed_cell = displayed_cell(data, view_data);
+    dyn_container(
+        move || displayed_cell.get(),
+        move |displayed_cell: CellPos| {
+            let ancestors = displayed_cell.ancestors();
+            let count = ancestors.len();
+            h_stack_from_iter(ancestors.into_iter().enumerate().map(|(i, pos)| {
+                let part_name = {
+                    let pos = pos.clone();
+                    move || {
+                        let title =
+                            data.with(|data| data.get_cell(&pos).map(|cell| cell.title.clone()));
+                        name(title, &pos)
+                    }
+                };
+                let part = if i + 1 == count {
+                    // we are already there
+                    label(part_name).style(|s| s.font_bold()).into_any()
+                } else {
+                    button(label(part_name))
+                        .action(move || {
+                            view_data.update(|view_data| view_data.displayed_cell = pos.clone())
+                        })
+                        .into_any()
+                };
+                h_stack((
+                    if i > 0 { ">" } else { "" }.style(|s| s.padding_horiz(5)),
+                    part,
+                ))
+                .style(|s| s.items_center())
+            }))
+            .style(|s| s.items_center())
+        },
+    )
+    .style(move |s| {
+        s.width_full()
+            .padding(5)
+            .border(Stroke::new(1.0))
+            .border_color(my_theme.border)
+    })
+}
+
+// the title of the cell, or its coordinates when it has none
+fn name(title: Option<String>, pos: &CellPos) -> String {
+    match title {
+        Some(title) if !title.is_empty() => title,
+        _ if pos.is_root() => "root".into(),
+        _ => {
+            let (row, col) = pos.last();
+            format!("({row}, {col})")
+        }
+    }
+}
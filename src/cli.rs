@@ -0,0 +1,336 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use graphyr_core::{
+    cell::CellPos,
+    export, format,
+    integrity::{self, DanglingArrows},
+    Data,
+};
+
+use crate::{graphyr_view, theme::MyTheme};
+
+pub const USAGE: &str = "\
+usage:
+    graphyr [<document.ron>]         open the editor, optionally with a document loaded
+    graphyr export <document.ron> [--svg <out.svg>] [--png <out.png>] [--scale <factor>] [--cell <row,col/...>]
+                                     render the document (or one of its cells) without a window
+    graphyr validate <document.ron>  check that the document can be loaded and has no problems
+    graphyr repair <document.ron> [--reattach]
+                                     fix the problems of the document in place, arrows pointing
+                                     to missing cells are removed, or attached to the highest cell
+    graphyr stats <document.ron>     print the size of the document
+    graphyr help                     print this message";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Gui(Option<PathBuf>),
+    Export {
+        document: PathBuf,
+        svg: Option<PathBuf>,
+        png: Option<PathBuf>,
+        scale: f32,
+        cell: CellPos,
+    },
+    Validate(PathBuf),
+    Repair {
+        document: PathBuf,
+        reattach: bool,
+    },
+    Stats(PathBuf),
+    Help,
+}
+
+impl Command {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let Some(first) = args.next() else {
+            return Ok(Self::Gui(None));
+        };
+
+        let command = match first.as_str() {
+            "help" | "--help" | "-h" => Self::Help,
+            "validate" => Self::Validate(document(args.next())?),
+            "stats" => Self::Stats(document(args.next())?),
+            "repair" => {
+                let document = document(args.next())?;
+                let reattach = match args.next() {
+                    None => false,
+                    Some(flag) if flag == "--reattach" => true,
+                    Some(flag) => bail!("unknown option {flag}"),
+                };
+                Self::Repair { document, reattach }
+            }
+            "export" => {
+                let document = document(args.next())?;
+                let (mut svg, mut png, mut scale, mut cell) = (None, None, 1., CellPos::new());
+                while let Some(flag) = args.next() {
+                    let value = args
+                        .next()
+                        .with_context(|| format!("missing value for {flag}"))?;
+                    match flag.as_str() {
+                        "--svg" => svg = Some(value.into()),
+                        "--png" => png = Some(value.into()),
+                        "--scale" => {
+                            scale = value
+                                .parse()
+                                .with_context(|| format!("invalid scale \"{value}\""))?
+                        }
+                        "--cell" => {
+                            cell = value
+                                .parse()
+                                .with_context(|| format!("invalid cell \"{value}\""))?
+                        }
+                        _ => bail!("unknown option {flag}"),
+                    }
+                }
+                if svg.is_none() && png.is_none() {
+                    bail!("export needs at least one of --svg and --png");
+                }
+                Self::Export {
+                    document,
+                    svg,
+                    png,
+                    scale,
+                    cell,
+                }
+            }
+            _ if first.starts_with('-') => bail!("unknown option {first}"),
+            _ => Self::Gui(Some(first.into())),
+        };
+
+        if let Some(arg) = args.next() {
+            bail!("unexpected argument {arg}");
+        }
+        Ok(command)
+    }
+
+    pub fn run(self) -> anyhow::Result<()> {
+        match self {
+            Self::Gui(path) => {
+                // the document is parsed by the application, so failures end up in its notification
+                let document = path.map(|path| read(&path)).transpose()?;
+                floem::launch(move || graphyr_view(document));
+            }
+            Self::Help => println!("{USAGE}"),
+            Self::Validate(path) => {
+                let problems = integrity::check(&load(&path)?);
+                if !problems.is_empty() {
+                    for problem in &problems {
+                        eprintln!("{}: {problem}", path.display());
+                    }
+                    bail!("{} problems found", problems.len());
+                }
+                println!("{}: ok", path.display());
+            }
+            Self::Repair { document, reattach } => {
+                let mut data = load(&document)?;
+                let problems = integrity::check(&data);
+                let dangling = if reattach {
+                    DanglingArrows::Reattach(data.cell.id.clone())
+                } else {
+                    DanglingArrows::Remove
+                };
+                integrity::repair(&mut data, &dangling);
+                format::save_file(&data, &document)?;
+                println!("{}: {} problems fixed", document.display(), problems.len());
+            }
+            Self::Stats(path) => print_stats(&load(&path)?),
+            Self::Export {
+                document,
+                svg,
+                png,
+                scale,
+                cell,
+            } => {
+                let mut data = load(&document)?;
+                // tables without their cells can't be laid out
+                integrity::repair_tables(&mut data);
+                data.get_cell(&cell)
+                    .with_context(|| format!("there is no cell at \"{cell}\""))?;
+                let theme = MyTheme::default().export_theme();
+                if let Some(path) = svg {
+                    export::svg::save(&data, &cell, &theme, &path)?;
+                }
+                if let Some(path) = png {
+                    export::png::save(&data, Some(&cell), &theme, scale, &path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn document(arg: Option<String>) -> anyhow::Result<PathBuf> {
+    arg.map(PathBuf::from).context("missing the document path")
+}
+
+fn read(path: &Path) -> anyhow::Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn load(path: &Path) -> anyhow::Result<Data> {
+    format::load_bytes(&read(path)?).with_context(|| format!("failed to load {}", path.display()))
+}
+
+fn print_stats(data: &Data) {
+    let (mut cells, mut tables, mut depth) = (0, 0, 0);
+    data.cell.for_each_cell(&mut |cell| {
+        cells += 1;
+        if cell.table.is_some() {
+            tables += 1;
+        }
+        depth = depth.max(cell.hierarchy_depth);
+    });
+    println!("cells: {cells}");
+    println!("tables: {tables}");
+    println!("depth: {depth}");
+
+    let layers = &data.configuration.layers;
+    println!("layers: {}", layers.len());
+    for layer in layers {
+        println!(
+            "    {}: {} arrows{}",
+            layer.name,
+            layer.arrows.len(),
+            if layer.enabled { ", enabled" } else { "" }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Command> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn opens_editor() {
+        assert_eq!(parse(&[]).unwrap(), Command::Gui(None));
+        assert_eq!(
+            parse(&["doc.ron"]).unwrap(),
+            Command::Gui(Some("doc.ron".into()))
+        );
+        // anything that is no command is a document
+        assert_eq!(
+            parse(&["exprot"]).unwrap(),
+            Command::Gui(Some("exprot".into()))
+        );
+        assert_eq!(
+            error(&["doc.ron", "other.ron"]),
+            "unexpected argument other.ron"
+        );
+        assert_eq!(error(&["--verbose"]), "unknown option --verbose");
+    }
+
+    #[test]
+    fn parses_help() {
+        for flag in ["help", "--help", "-h"] {
+            assert_eq!(parse(&[flag]).unwrap(), Command::Help);
+        }
+        assert_eq!(error(&["help", "export"]), "unexpected argument export");
+    }
+
+    #[test]
+    fn parses_document_commands() {
+        assert_eq!(
+            parse(&["validate", "doc.ron"]).unwrap(),
+            Command::Validate("doc.ron".into())
+        );
+        assert_eq!(
+            parse(&["stats", "doc.ron"]).unwrap(),
+            Command::Stats("doc.ron".into())
+        );
+        for command in ["validate", "stats", "repair", "export"] {
+            assert_eq!(error(&[command]), "missing the document path");
+        }
+        assert_eq!(
+            error(&["stats", "doc.ron", "more"]),
+            "unexpected argument more"
+        );
+    }
+
+    #[test]
+    fn parses_repair() {
+        assert_eq!(
+            parse(&["repair", "doc.ron"]).unwrap(),
+            Command::Repair {
+                document: "doc.ron".into(),
+                reattach: false,
+            }
+        );
+        assert_eq!(
+            parse(&["repair", "doc.ron", "--reattach"]).unwrap(),
+            Command::Repair {
+                document: "doc.ron".into(),
+                reattach: true,
+            }
+        );
+        assert_eq!(
+            error(&["repair", "doc.ron", "--remove"]),
+            "unknown option --remove"
+        );
+        assert_eq!(
+            error(&["repair", "doc.ron", "--reattach", "--reattach"]),
+            "unexpected argument --reattach"
+        );
+    }
+
+    #[test]
+    fn parses_export() {
+        assert_eq!(
+            parse(&["export", "doc.ron", "--svg", "out.svg"]).unwrap(),
+            Command::Export {
+                document: "doc.ron".into(),
+                svg: Some("out.svg".into()),
+                png: None,
+                scale: 1.,
+                cell: CellPos::new(),
+            }
+        );
+        assert_eq!(
+            parse(&["export", "doc.ron", "--png", "out.png", "--scale", "2", "--cell", "0,1/2,0",])
+                .unwrap(),
+            Command::Export {
+                document: "doc.ron".into(),
+                svg: None,
+                png: Some("out.png".into()),
+                scale: 2.,
+                cell: CellPos::new().child(0, 1).child(2, 0),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_export_options() {
+        assert_eq!(
+            error(&["export", "doc.ron"]),
+            "export needs at least one of --svg and --png"
+        );
+        assert_eq!(
+            error(&["export", "doc.ron", "--svg"]),
+            "missing value for --svg"
+        );
+        assert_eq!(
+            error(&["export", "doc.ron", "--pdf", "out.pdf"]),
+            "unknown option --pdf"
+        );
+        assert_eq!(
+            error(&["export", "doc.ron", "--svg", "out.svg", "--scale", "big"]),
+            "invalid scale \"big\""
+        );
+        assert_eq!(
+            error(&["export", "doc.ron", "--svg", "out.svg", "--cell", "1"]),
+            "invalid cell \"1\""
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use floem::{event::EventListener, prelude::*, reactive::create_effect};
+use graphyr_core::Data;
+
+// how many snapshots are kept in each direction
+const MAX_SNAPSHOTS: usize = 100;
+
+// every edit of the document goes through here, we keep copies of Data taken before
+// the edit, so undo/redo simply swaps the whole document
+#[derive(Clone, Copy)]
+pub struct History {
+    data: RwSignal<Data>,
+    undo_stack: RwSignal<Vec<Data>>,
+    redo_stack: RwSignal<Vec<Data>>,
+    // the selected arrow of the view, its indices may point elsewhere in a restored document
+    selected_arrow: RwSignal<Option<(usize, usize)>>,
+}
+
+impl History {
+    pub fn new(data: RwSignal<Data>, selected_arrow: RwSignal<Option<(usize, usize)>>) -> Self {
+        Self {
+            data,
+            undo_stack: RwSignal::new(vec![]),
+            redo_stack: RwSignal::new(vec![]),
+            selected_arrow,
+        }
+    }
+
+    // state of the current document
+    pub fn snapshot(&self) -> Data {
+        self.data.get_untracked()
+    }
+
+    // remembers the current state, call it right before changing the document
+    pub fn record(&self) {
+        self.push(self.snapshot());
+    }
+
+    // remembers an already taken snapshot, used for edits that span many events (typing)
+    pub fn push(&self, snapshot: Data) {
+        self.undo_stack.update(|stack| {
+            stack.push(snapshot);
+            if stack.len() > MAX_SNAPSHOTS {
+                stack.remove(0);
+            }
+        });
+        self.redo_stack.update(|stack| stack.clear());
+    }
+
+    // records the current state and then applies the edit
+    pub fn edit(&self, edit: impl FnOnce(&mut Data)) {
+        self.record();
+        self.data.update(edit);
+    }
+
+    // a text input editing a string inside of the document, `get` returns None when
+    // the string doesn't exist (anymore), typing produces many changes, so they are merged
+    // into a single entry that is taken when the input gets focus and recorded when it loses it
+    pub fn text_input(
+        &self,
+        get: impl Fn(&Data) -> Option<String> + 'static,
+        set: impl Fn(&mut Data, String) + 'static,
+    ) -> TextInput {
+        let history = *self;
+        let data = self.data;
+        let get = Rc::new(get);
+        let text = RwSignal::new(data.with_untracked(|data| get(data)).unwrap_or_default());
+
+        // the document changed (e.g. by undo), show its value
+        create_effect({
+            let get = get.clone();
+            move |_| {
+                if let Some(value) = data.with(|data| get(data)) {
+                    if text.with_untracked(|text| text != &value) {
+                        text.set(value);
+                    }
+                }
+            }
+        });
+        // the user typed something, write it into the document
+        create_effect({
+            let get = get.clone();
+            move |_| {
+                let value = text.get();
+                let current = data.with_untracked(|data| get(data));
+                if current.is_some_and(|current| current != value) {
+                    data.update(|data| set(data, value));
+                }
+            }
+        });
+
+        let pending: RwSignal<Option<(Data, Option<String>)>> = RwSignal::new(None);
+        text_input(text)
+            .on_event_cont(EventListener::FocusGained, {
+                let get = get.clone();
+                move |_| {
+                    let value = data.with_untracked(|data| get(data));
+                    pending.set(Some((history.snapshot(), value)));
+                }
+            })
+            .on_event_cont(EventListener::FocusLost, move |_| {
+                if let Some((snapshot, old_value)) =
+                    pending.try_update(|pending| pending.take()).flatten()
+                {
+                    if data.with_untracked(|data| get(data)) != old_value {
+                        history.push(snapshot);
+                    }
+                }
+            })
+    }
+
+    pub fn undo(&self) {
+        if let Some(snapshot) = self.undo_stack.try_update(|stack| stack.pop()).flatten() {
+            let current = self.snapshot();
+            self.redo_stack.update(|stack| stack.push(current));
+            self.restore(snapshot);
+        }
+    }
+
+    pub fn redo(&self) {
+        if let Some(snapshot) = self.redo_stack.try_update(|stack| stack.pop()).flatten() {
+            let current = self.snapshot();
+            self.undo_stack.update(|stack| stack.push(current));
+            self.restore(snapshot);
+        }
+    }
+
+    fn restore(&self, snapshot: Data) {
+        self.selected_arrow.set(None);
+        self.data.set(snapshot);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_stack.with(|stack| !stack.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_stack.with(|stack| !stack.is_empty())
+    }
+
+    // forget everything, used when a different document is loaded
+    pub fn clear(&self) {
+        self.undo_stack.update(|stack| stack.clear());
+        self.redo_stack.update(|stack| stack.clear());
+    }
+}
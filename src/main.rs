@@ -1,50 +1,106 @@
-use data::Data;
-use floem::{prelude::*, reactive::create_effect};
+use std::{env, process::ExitCode};
+
+use cli::{Command, USAGE};
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, Modifiers},
+    prelude::*,
+    reactive::create_effect,
+};
+use graphyr_core::{format, integrity, Data};
+use history::History;
+use notification::Notification;
 use theme::MyTheme;
 use view_data::ViewData;
+use views::data_view;
 
-mod data;
+mod breadcrumb;
+mod cli;
+mod history;
 mod main_view;
+mod notification;
+mod problems;
 mod theme;
-mod utils;
 mod view_data;
+mod views;
+
+fn main() -> ExitCode {
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("error: {error:#}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-fn main() {
-    floem::launch(graphyr_view);
+    if let Err(error) = command.run() {
+        eprintln!("error: {error:#}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
 }
 
-fn graphyr_view() -> impl IntoView {
+pub fn graphyr_view(document: Option<Vec<u8>>) -> impl IntoView {
     // used for loading new data
-    let temp_data: RwSignal<Option<Vec<u8>>> = RwSignal::new(None);
+    let temp_data: RwSignal<Option<Vec<u8>>> = RwSignal::new(document);
     let data_signal = RwSignal::new(Data::new());
+    let notification = Notification::new();
 
     // we want everything to react to changes of view_data and then get new values from data
     // temporary settings
     let view_data = RwSignal::new(ViewData::new());
+    let history = History::new(data_signal, view_data.get_untracked().selected_arrow);
 
     create_effect({
         move |_| {
             if let Some(new_value) = temp_data.get().take() {
-                let deserialized_data =
-                    ron::from_str(&String::from_utf8(new_value).unwrap()).unwrap();
-                view_data.update(|view_data| view_data.reset());
-                data_signal.set(deserialized_data);
+                // on failure the current document stays as it is
+                match format::load_bytes(&new_value) {
+                    Ok(mut deserialized_data) => {
+                        // tables without their cells can't be shown, the rest of the problems
+                        // are offered to be repaired below
+                        integrity::repair_tables(&mut deserialized_data);
+                        view_data.update(|view_data| view_data.reset());
+                        history.clear();
+                        data_signal.set(deserialized_data);
+                        // documents edited by hand (or by older versions) may be broken
+                        problems::report_problems(data_signal, history, notification, None);
+                    }
+                    Err(error) => notification.error(error.context("failed to load the document")),
+                }
             }
         }
     });
 
     let my_theme = MyTheme::default();
 
-    dyn_container(move || data_signal.get(), {
-        let my_theme = my_theme.clone();
-        move |data: Data| {
-            data.build_view(
-                view_data,
-                data_signal.clone(),
-                temp_data.clone(),
-                my_theme.clone(),
-            )
-            .style(|s| s.width_full())
+    v_stack((
+        notification.build_view(my_theme.clone()),
+        data_view(
+            data_signal,
+            view_data,
+            temp_data,
+            history,
+            notification,
+            my_theme.clone(),
+        )
+        .style(|s| s.size_full()),
+    ))
+    .on_event_cont(EventListener::KeyDown, move |event| {
+        if let Event::KeyDown(key_event) = event {
+            let modifiers = key_event.modifiers;
+            if !modifiers.contains(Modifiers::CONTROL) {
+                return;
+            }
+            if let Key::Character(character) = &key_event.key.logical_key {
+                if character.eq_ignore_ascii_case("z") {
+                    if modifiers.contains(Modifiers::SHIFT) {
+                        history.redo();
+                    } else {
+                        history.undo();
+                    }
+                }
+            }
         }
     })
     .style(move |s| theme::theme(s, &my_theme))
@@ -1,72 +1,408 @@
-use std::{collections::HashMap, rc::Rc};
+use std::collections::HashMap;
 
 use floem::{
-    kurbo::{BezPath, Point, Rect, Stroke},
-    prelude::{RwSignal, SignalGet as _},
+    action::show_context_menu,
+    event::{Event, EventPropagation},
+    kurbo::{BezPath, Circle, Point, Rect, Size, Stroke},
+    menu::{Menu, MenuEntry, MenuItem},
+    peniko::Color,
+    prelude::{RwSignal, SignalGet as _, SignalUpdate as _, SignalWith as _},
+    reactive::create_effect,
+    text::{Attrs, AttrsList, TextLayout},
     views::{dyn_container, Decorators as _},
     Renderer, View, ViewId,
 };
+use graphyr_core::{
+    cell::{Cell, CellId},
+    configuration::arrow::{Anchor, Arrow},
+    drawing::{self, Stub},
+    export::layout::LABEL_FONT_SIZE,
+    routing::{distance_to_line, pin, place, Route, Router},
+    Data,
+};
 
 use crate::{
-    data::{
-        cell::{Cell, CellId},
-        configuration::{arrow::Arrow, layer::Layer, Configuration},
-        Data,
+    history::History,
+    notification::Notification,
+    theme::{from_core, to_core, MyTheme},
+    view_data::{displayed_cell, ViewData},
+    views::{
+        cell::cell_view,
+        configuration::{get_arrow, get_arrow_mut},
     },
-    theme::MyTheme,
-    utils::{compute_path, Segment},
-    view_data::ViewData,
 };
 
+// how far from an arrow the pointer can be to pick it, besides half of its width
+const HIT_DISTANCE: f64 = 4.0;
+const HANDLE_RADIUS: f64 = 5.0;
+
 // we do that to draw over inner
 pub struct Main {
     id: ViewId,
+    data: RwSignal<Data>,
     view_data: RwSignal<ViewData>,
-    main_cell: Rc<Cell>,
-    layers: RwSignal<Vec<Layer>>,
-    positions: HashMap<CellId, Rect>,
+    // keeps the routes while the cells stay in place, painting doesn't route again
+    router: Router,
+    // rect of the displayed cell, arrows leaving it end at its border
+    bounds: Rect,
+    history: History,
+    // the arrows of the last paint, in the order they were painted
+    painted: Vec<PaintedArrow>,
+    // (layer, arrow) under the pointer
+    hovered: Option<(usize, usize)>,
+    // handles of the selected arrow
+    handles: Vec<Handle>,
+    drag: Option<Drag>,
+    my_theme: MyTheme,
+}
+
+struct PaintedArrow {
+    layer: usize,
+    arrow: usize,
+    line: BezPath,
+    width: f64,
+}
+
+// a point of the selected arrow that can be dragged, either one of its waypoints or
+// the middle of a segment, which adds a new waypoint there
+#[derive(Clone)]
+struct Handle {
+    center: Point,
+    // the points before and after it on the path
+    neighbors: [Point; 2],
+    // index of the waypoint, or where the new one is inserted
+    waypoint: usize,
+    insert: bool,
+}
+
+struct Drag {
+    layer: usize,
+    arrow: usize,
+    handle: Handle,
+    pos: Point,
 }
 
 impl Main {
     pub fn new(
+        data: RwSignal<Data>,
         view_data: RwSignal<ViewData>,
-        configuration: &Configuration,
-        main_cell: Rc<Cell>,
+        history: History,
+        notification: Notification,
         my_theme: MyTheme,
     ) -> Self {
-        let layers = configuration.layers;
-        let inner = dyn_container(move || view_data.get(), {
-            // the cell that we view
-            {
-                let show_border = configuration.show_border;
-                let show_panes = configuration.show_panes;
-                let main_cell = main_cell.clone();
-                move |view_data: ViewData| {
-                    Data::get_cell(&main_cell, &view_data.displayed_cell).build_view(
-                        show_border,
-                        show_panes,
-                        layers,
-                        view_data.arrow_start_id,
-                        my_theme.clone(),
-                    )
-                }
+        // the cell that we view
+        let displayed_cell = displayed_cell(data, view_data);
+        let inner = dyn_container(move || displayed_cell.get(), {
+            let my_theme = my_theme.clone();
+            move |pos| {
+                cell_view(
+                    data,
+                    view_data,
+                    pos,
+                    history,
+                    notification,
+                    my_theme.clone(),
+                )
             }
         })
         .style(|s| s.size_full());
 
         let id = ViewId::new();
         id.set_children(vec![inner]);
+
+        // arrows are painted from the data, so every change of it may move them
+        let selected_arrow = view_data.get_untracked().selected_arrow;
+        create_effect(move |_| {
+            data.with(|_| ());
+            selected_arrow.with(|_| ());
+            id.request_paint();
+        });
+
         Self {
             id,
+            data,
             view_data,
-            main_cell,
-            layers,
-            positions: HashMap::new(),
+            router: Router::new(HashMap::new(), HashMap::new()),
+            bounds: Rect::ZERO,
+            history,
+            painted: vec![],
+            hovered: None,
+            handles: vec![],
+            drag: None,
+            my_theme,
+        }
+    }
+
+    fn selected_arrow(&self) -> RwSignal<Option<(usize, usize)>> {
+        self.view_data.get_untracked().selected_arrow
+    }
+
+    // the topmost arrow under the point
+    fn arrow_at(&self, point: Point) -> Option<(usize, usize)> {
+        self.painted
+            .iter()
+            .rev()
+            .find(|painted| {
+                distance_to_line(&painted.line, point) <= HIT_DISTANCE + painted.width / 2.
+            })
+            .map(|painted| (painted.layer, painted.arrow))
+    }
+
+    // waypoints come first, so they are picked over the middles of segments next to them
+    fn handle_at(&self, point: Point) -> Option<&Handle> {
+        self.handles
+            .iter()
+            .find(|handle| handle.center.distance(point) <= HANDLE_RADIUS + 2.)
+    }
+
+    // the waypoints of the arrow that are on its path, and the middles of its segments
+    fn handles_of(&self, arrow: &Arrow, path: &[Point]) -> Vec<Handle> {
+        // (index of the waypoint, index of its point in the path)
+        let mut placed = vec![];
+        let mut start = 0;
+        for (index, waypoint) in arrow.waypoints.iter().enumerate() {
+            let Some(point) = place(waypoint, self.router.boxes()) else {
+                continue;
+            };
+            // the lanes of shared parts may have moved it a bit
+            let found = path[start..]
+                .iter()
+                .position(|p| p.distance(point) <= 2. * HANDLE_RADIUS);
+            if let Some(at) = found {
+                start += at;
+                placed.push((index, start));
+            }
         }
+
+        let last = path.len() - 1;
+        let mut handles = placed
+            .iter()
+            .filter(|(_, at)| *at > 0 && *at < last)
+            .map(|(waypoint, at)| Handle {
+                center: path[*at],
+                neighbors: [path[at - 1], path[at + 1]],
+                waypoint: *waypoint,
+                insert: false,
+            })
+            .collect::<Vec<_>>();
+        handles.extend(path.windows(2).enumerate().map(|(at, segment)| {
+            // after the waypoints that come before the segment
+            let waypoint = placed
+                .iter()
+                .rev()
+                .find(|(_, placed_at)| *placed_at <= at)
+                .map_or(0, |(waypoint, _)| waypoint + 1);
+            Handle {
+                center: segment[0].midpoint(segment[1]),
+                neighbors: [segment[0], segment[1]],
+                waypoint,
+                insert: true,
+            }
+        }));
+        handles
+    }
+
+    // a glow under the line and outlines around the ends
+    fn paint_highlight(
+        &self,
+        cx: &mut floem::context::PaintCx,
+        arrow: &Arrow,
+        line: &BezPath,
+        color: Color,
+    ) {
+        cx.stroke(line, color.with_alpha(0.35), &Stroke::new(arrow.width + 6.));
+        for (id, anchor) in [
+            (&arrow.from, arrow.from_anchor),
+            (&arrow.to, arrow.to_anchor),
+        ] {
+            let rects = match anchor {
+                Anchor::Title => self.router.positions(),
+                _ => self.router.boxes(),
+            };
+            if let Some(rect) = rects.get(id) {
+                cx.stroke(&rect.inflate(2., 2.), &color, &Stroke::new(2.));
+            }
+        }
+    }
+
+    fn paint_handles(&self, cx: &mut floem::context::PaintCx, color: Color) {
+        for handle in &self.handles {
+            if handle.insert {
+                let circle = Circle::new(handle.center, HANDLE_RADIUS - 1.);
+                cx.fill(&circle, self.my_theme.background, 0.0);
+                cx.stroke(&circle, &color, &Stroke::new(1.0));
+            } else {
+                let circle = Circle::new(handle.center, HANDLE_RADIUS);
+                cx.fill(&circle, &color, 0.0);
+                cx.stroke(&circle, &self.my_theme.foreground, &Stroke::new(1.0));
+            }
+        }
+    }
+
+    // the dragged point with lines to its neighbors, the arrow is routed again when it's dropped
+    fn paint_drag(&self, cx: &mut floem::context::PaintCx, drag: &Drag) {
+        let mut line_path = BezPath::new();
+        line_path.move_to(drag.handle.neighbors[0]);
+        line_path.line_to(drag.pos);
+        line_path.line_to(drag.handle.neighbors[1]);
+        cx.stroke(
+            &line_path,
+            &self.my_theme.foreground,
+            &Stroke::new(1.0).with_dashes(0.0, [4.0, 4.0]),
+        );
+        let circle = Circle::new(drag.pos, HANDLE_RADIUS);
+        cx.fill(&circle, self.my_theme.foreground, 0.0);
+    }
+
+    // the waypoint is kept relative to the innermost cell under it
+    fn drop_handle(&self, drag: Drag) {
+        let Some(waypoint) = pin(drag.pos, self.router.boxes()) else {
+            return;
+        };
+        self.history.edit(|data| {
+            let Some(arrow) = get_arrow_mut(data, drag.layer, drag.arrow) else {
+                return;
+            };
+            let index = drag.handle.waypoint.min(arrow.waypoints.len());
+            if drag.handle.insert {
+                arrow.waypoints.insert(index, waypoint);
+            } else if let Some(old) = arrow.waypoints.get_mut(index) {
+                *old = waypoint;
+            }
+        });
+    }
+
+    fn show_arrow_menu(&self, (layer, arrow): (usize, usize), waypoint: Option<usize>, pos: Point) {
+        let history = self.history;
+        let selected_arrow = self.selected_arrow();
+        let background = to_core(self.my_theme.background);
+        let Some((has_waypoints, layers)) = self.data.with_untracked(|data| {
+            let has_waypoints = get_arrow(data, layer, arrow, |a| !a.waypoints.is_empty())?;
+            let layers = data.configuration.layers.iter().map(|l| l.name.clone());
+            Some((has_waypoints, layers.collect::<Vec<_>>()))
+        }) else {
+            return;
+        };
+
+        // the indices of the arrows after it change, so nothing stays selected
+        let menu = Menu::new("")
+            .entry(MenuEntry::Item(MenuItem::new("Delete").action(move || {
+                selected_arrow.set(None);
+                history.edit(|data| {
+                    if let Some(l) = data.configuration.layers.get_mut(layer) {
+                        if arrow < l.arrows.len() {
+                            l.arrows.remove(arrow);
+                        }
+                    }
+                })
+            })))
+            .entry(MenuEntry::Item(MenuItem::new("Recolor").action(
+                move || {
+                    history.edit(|data| {
+                        let Some(palette) = data.configuration.layers.get(layer).map(|l| l.palette)
+                        else {
+                            return;
+                        };
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            arrow.recolor(palette, background);
+                        }
+                    })
+                },
+            )))
+            .entry(MenuEntry::Item(MenuItem::new("Reverse direction").action(
+                move || {
+                    history.edit(|data| {
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            arrow.reverse();
+                        }
+                    })
+                },
+            )));
+        let move_menu = layers
+            .into_iter()
+            .enumerate()
+            .filter(|(to, _)| *to != layer)
+            .fold(Menu::new("Move to layer"), |menu, (to, name)| {
+                menu.entry(MenuEntry::Item(MenuItem::new(name).action(move || {
+                    selected_arrow.set(None);
+                    history.edit(|data| data.configuration.move_arrow(layer, arrow, to))
+                })))
+            });
+        let menu = menu.entry(MenuEntry::SubMenu(move_menu));
+
+        let menu = if let Some(waypoint) = waypoint {
+            menu.entry(MenuEntry::Item(MenuItem::new("Remove waypoint").action(
+                move || {
+                    history.edit(|data| {
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            if waypoint < arrow.waypoints.len() {
+                                arrow.waypoints.remove(waypoint);
+                            }
+                        }
+                    })
+                },
+            )))
+        } else {
+            menu
+        };
+        let menu = menu.entry(MenuEntry::Item(
+            MenuItem::new("Reset to auto route")
+                .enabled(has_waypoints)
+                .action(move || {
+                    history.edit(|data| {
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            arrow.waypoints.clear();
+                        }
+                    })
+                }),
+        ));
+        show_context_menu(menu, Some(pos));
+    }
+
+    // a dashed line from the cell to the closest border of the displayed cell, it represents
+    // an arrow whose other end is not visible
+    fn paint_stub(&self, cx: &mut floem::context::PaintCx, stub: &Stub) {
+        let color = from_core(stub.color);
+        let mut line_path = BezPath::new();
+        line_path.move_to(stub.start);
+        line_path.line_to(stub.end);
+        cx.stroke(
+            &line_path,
+            &color,
+            &Stroke::new(Stub::WIDTH).with_dashes(0.0, Stub::DASHES),
+        );
+        cx.fill(&Circle::new(stub.end, Stub::DOT_RADIUS), &color, 0.0);
+    }
+
+    // the label sits in a box on the longest segment of the path, see `label_size`
+    fn paint_label(&self, cx: &mut floem::context::PaintCx, rect: Rect, label: &str, color: Color) {
+        cx.fill(&rect, self.my_theme.background, 0.0);
+        cx.stroke(&rect, &color, &Stroke::new(1.0));
+        cx.draw_text(&label_layout(label, color), rect.origin() + (4., 2.));
+    }
+
+    // a "!" in a circle, it marks an arrow that goes through cells because there was
+    // no way around them
+    fn paint_unroutable_mark(&self, cx: &mut floem::context::PaintCx, center: Point, color: Color) {
+        let circle = Circle::new(center, Route::MARK_RADIUS);
+        cx.fill(&circle, self.my_theme.error_background, 0.0);
+        cx.stroke(&circle, &color, &Stroke::new(1.0));
+        let mut text_layout = TextLayout::new();
+        text_layout.set_text(
+            "!",
+            AttrsList::new(
+                Attrs::new()
+                    .font_size(LABEL_FONT_SIZE as f32)
+                    .color(self.my_theme.foreground),
+            ),
+        );
+        let size = text_layout.size();
+        cx.draw_text(&text_layout, center - (size.width / 2., size.height / 2.));
     }
 
     fn handle_cell_layout(
         positions: &mut HashMap<CellId, Rect>,
+        boxes: &mut HashMap<CellId, Rect>,
         cx: &mut floem::context::ComputeLayoutCx,
         cell: &Cell,
         cell_view: ViewId,
@@ -74,27 +410,41 @@ impl Main {
         let cell_text = cell_view.children()[0];
         let cell_rect = cell_text.layout_rect();
         positions.insert(cell.id.clone(), cell_rect);
+        boxes.insert(cell.id.clone(), cell_view.layout_rect());
         let cell_table = cell_view.children()[1]; // container made by cell
 
-        if let Some(table) = cell.table.get_untracked() {
+        if let Some(table) = &cell.table {
             let cell_table = cell_table.children()[0]; // container made by table
             let v_stack = cell_table.children()[0];
             let _v_pane = v_stack.children()[0];
 
-            let cells = table.cells.get_untracked();
-            let cells_data = cells.data.borrow();
-            for (row_id, row) in cells_data.iter().enumerate() {
+            for (row_id, row) in table.cells.data.iter().enumerate() {
                 let h_stack = v_stack.children()[row_id + 1];
                 let _h_pane = h_stack.children()[0];
                 for (cell_id, cell) in row.iter().enumerate() {
                     let cell_view = h_stack.children()[cell_id + 1];
-                    Self::handle_cell_layout(positions, cx, cell, cell_view);
+                    Self::handle_cell_layout(positions, boxes, cx, cell, cell_view);
                 }
             }
         }
     }
 }
 
+fn label_layout(label: &str, color: Color) -> TextLayout {
+    let mut text_layout = TextLayout::new();
+    text_layout.set_text(
+        label,
+        AttrsList::new(Attrs::new().font_size(LABEL_FONT_SIZE as f32).color(color)),
+    );
+    text_layout
+}
+
+// the box of a label, its text with some room around it
+fn label_size(label: &str) -> Size {
+    let text_size = label_layout(label, Color::BLACK).size();
+    Size::new(text_size.width + 8., text_size.height + 4.)
+}
+
 impl View for Main {
     fn id(&self) -> floem::ViewId {
         self.id
@@ -104,97 +454,181 @@ impl View for Main {
         &mut self,
         cx: &mut floem::context::ComputeLayoutCx,
     ) -> Option<floem::kurbo::Rect> {
-        self.positions.clear();
-
         let container = self.id.children()[0];
         let layout_rect = cx.compute_view_layout(container).unwrap();
 
-        let cell = Data::get_cell(
-            &self.main_cell,
-            &self.view_data.get_untracked().displayed_cell,
-        );
         let cell_view = container.children()[0];
-        Self::handle_cell_layout(&mut self.positions, cx, cell, cell_view);
+        self.bounds = cell_view.layout_rect();
+        let displayed_cell = self.view_data.get_untracked().displayed_cell;
+        let mut positions = HashMap::new();
+        let mut boxes = HashMap::new();
+        self.data.with_untracked(|data| {
+            let (cell, _) = data.get_cell_or_highest(&displayed_cell);
+            Self::handle_cell_layout(&mut positions, &mut boxes, cx, cell, cell_view);
+        });
+        self.router.set_positions(positions, boxes);
         Some(layout_rect)
     }
 
-    fn paint(&mut self, cx: &mut floem::context::PaintCx) {
-        cx.paint_children(self.id);
-        for layer in self
-            .layers
-            .get_untracked()
-            .iter()
-            .filter(|layer| layer.enabled.get_untracked())
-        {
-            for Arrow {
-                from,
-                to,
-                color,
-                padding,
-            } in &layer.arrows.get_untracked()
-            {
-                let from_rect = self.positions[from];
-                let to_rect = self.positions[to];
-                let center_segment = Segment {
-                    p1: from_rect.center(),
-                    p2: to_rect.center(),
-                };
+    fn event_before_children(
+        &mut self,
+        cx: &mut floem::context::EventCx,
+        event: &Event,
+    ) -> EventPropagation {
+        let selected_arrow = self.selected_arrow();
+        match event {
+            Event::PointerDown(pointer_event) => {
+                let pos = pointer_event.pos;
+                let selected = selected_arrow.get_untracked();
+                let handle = selected.and_then(|selected| Some((selected, self.handle_at(pos)?)));
+                if pointer_event.button.is_secondary() {
+                    if let Some((selected, handle)) = handle {
+                        let waypoint = (!handle.insert).then_some(handle.waypoint);
+                        self.show_arrow_menu(selected, waypoint, pos);
+                        return EventPropagation::Stop;
+                    }
+                    if let Some(arrow) = self.arrow_at(pos) {
+                        selected_arrow.set(Some(arrow));
+                        self.show_arrow_menu(arrow, None, pos);
+                        return EventPropagation::Stop;
+                    }
+                    return EventPropagation::Continue;
+                }
+                if !pointer_event.button.is_primary() {
+                    return EventPropagation::Continue;
+                }
 
-                let from_cross = center_segment.intersect_rect(&from_rect).unwrap();
-                let to_cross = center_segment.intersect_rect(&to_rect).unwrap();
-
-                // Pathfinding
-                let margin = padding.get_untracked();
-                let rest_rects = self
-                    .positions
-                    .iter()
-                    .filter_map(|(id, rect)| {
-                        if id != from && id != to {
-                            Some(rect.clone())
-                        } else {
-                            None
+                if let Some(((layer, arrow), handle)) = handle {
+                    self.drag = Some(Drag {
+                        layer,
+                        arrow,
+                        handle: handle.clone(),
+                        pos,
+                    });
+                    cx.update_active(self.id);
+                    self.id.request_paint();
+                    return EventPropagation::Stop;
+                }
+                match self.arrow_at(pos) {
+                    Some(arrow) => {
+                        selected_arrow.set(Some(arrow));
+                        EventPropagation::Stop
+                    }
+                    None => {
+                        if selected.is_some() {
+                            selected_arrow.set(None);
                         }
-                    })
-                    .collect::<Vec<_>>();
-
-                let path = compute_path(&rest_rects, &from_cross, &to_cross, margin).unwrap();
+                        EventPropagation::Continue
+                    }
+                }
+            }
+            Event::PointerMove(pointer_event) => {
+                let Some(drag) = &mut self.drag else {
+                    // the cells under it still get the event, for their own hover
+                    let hovered = self.arrow_at(pointer_event.pos);
+                    if hovered != self.hovered {
+                        self.hovered = hovered;
+                        self.id.request_paint();
+                    }
+                    return EventPropagation::Continue;
+                };
+                drag.pos = pointer_event.pos;
+                self.id.request_paint();
+                EventPropagation::Stop
+            }
+            Event::PointerUp(pointer_event) => {
+                let Some(mut drag) = self.drag.take() else {
+                    return EventPropagation::Continue;
+                };
+                drag.pos = pointer_event.pos;
+                // a click on a handle doesn't move it
+                if drag.pos.distance(drag.handle.center) > 2. {
+                    self.drop_handle(drag);
+                }
+                self.id.request_paint();
+                EventPropagation::Stop
+            }
+            Event::PointerLeave => {
+                if self.hovered.take().is_some() {
+                    self.id.request_paint();
+                }
+                EventPropagation::Continue
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
 
-                // Draw the main line.
-                let mut line_path = BezPath::new();
-                line_path.move_to(path[0]);
+    fn paint(&mut self, cx: &mut floem::context::PaintCx) {
+        cx.paint_children(self.id);
+        let data = self.data;
+        data.with_untracked(|data| {
+            self.painted.clear();
+            self.handles.clear();
+            let selected = self.selected_arrow().get_untracked();
+            let mut selected_color = None;
+            let drawing = drawing::draw(
+                &data.configuration,
+                &mut self.router,
+                self.bounds,
+                label_size,
+            );
+            for stub in &drawing.stubs {
+                self.paint_stub(cx, stub);
+            }
+            for drawn in drawing.arrows {
+                let arrow = drawn.arrow;
+                let color = from_core(arrow.color);
+                let key = (drawn.layer, drawn.index);
+                if [selected, self.hovered].contains(&Some(key)) {
+                    self.paint_highlight(cx, arrow, &drawn.line, color);
+                }
 
-                for point in &path[1..] {
-                    line_path.line_to(point.clone());
+                // The trunk of a bundle is painted with its first arrow.
+                if let Some((trunk, width)) = &drawn.trunk {
+                    let stroke = Stroke::new(*width).with_dashes(0.0, arrow.style.dashes(*width));
+                    cx.stroke(trunk, &color, &stroke);
                 }
 
-                let from_cross = path[path.len() - 2];
+                // Draw the main line.
+                let stroke =
+                    Stroke::new(arrow.width).with_dashes(0.0, arrow.style.dashes(arrow.width));
+                cx.stroke(&drawn.line, &color, &stroke);
 
-                cx.stroke(&line_path, &color.get_untracked(), &Stroke::new(2.0));
+                // Draw the heads at the ends of the path.
+                for head in &drawn.heads {
+                    if head.filled {
+                        cx.fill(&head.path, &color, 0.0);
+                    } else {
+                        cx.stroke(&head.path, &color, &Stroke::new(arrow.width));
+                    }
+                }
 
-                // Compute arrowhead at `to_cross`.
-                let dx = to_cross.x - from_cross.x;
-                let dy = to_cross.y - from_cross.y;
-                let theta = dy.atan2(dx);
-                let arrow_length = 8.0;
-                let arrow_angle = std::f64::consts::PI / 6.0; // 30° angle.
+                if let Some(center) = drawn.mark {
+                    self.paint_unroutable_mark(cx, center, color);
+                }
+                if let Some(rect) = drawn.label {
+                    self.paint_label(cx, rect, &arrow.label, color);
+                }
 
-                let left = Point {
-                    x: to_cross.x - arrow_length * (theta + arrow_angle).cos(),
-                    y: to_cross.y - arrow_length * (theta + arrow_angle).sin(),
-                };
-                let right = Point {
-                    x: to_cross.x - arrow_length * (theta - arrow_angle).cos(),
-                    y: to_cross.y - arrow_length * (theta - arrow_angle).sin(),
-                };
+                if selected == Some(key) {
+                    self.handles = self.handles_of(arrow, &drawn.route.points);
+                    selected_color = Some(color);
+                }
+                self.painted.push(PaintedArrow {
+                    layer: drawn.layer,
+                    arrow: drawn.index,
+                    line: drawn.line,
+                    width: arrow.width,
+                });
+            }
 
-                // Draw arrowhead as a filled triangle.
-                let mut arrow_path = BezPath::new();
-                arrow_path.move_to(to_cross);
-                arrow_path.line_to(left);
-                arrow_path.line_to(right);
-                arrow_path.close_path();
-                cx.fill(&arrow_path, &color.get_untracked(), 0.0);
+            // over all of the arrows, so they can always be grabbed
+            if let Some(color) = selected_color {
+                self.paint_handles(cx, color);
             }
+        });
+        if let Some(drag) = &self.drag {
+            self.paint_drag(cx, drag);
         }
     }
 }
@@ -0,0 +1,86 @@
+use std::{
+    rc::Rc,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use floem::{prelude::*, taffy::FlexDirection};
+
+use crate::theme::MyTheme;
+
+// a button of the notification, clicking it closes the notification
+#[derive(Clone)]
+pub struct NotificationAction {
+    pub name: String,
+    pub action: Rc<dyn Fn()>,
+}
+
+impl NotificationAction {
+    pub fn new(name: impl Into<String>, action: impl Fn() + 'static) -> Self {
+        Self {
+            name: name.into(),
+            action: Rc::new(action),
+        }
+    }
+}
+
+// a message shown at the top of the window, used to report failures (e.g. loading a broken file)
+#[derive(Clone, Copy)]
+pub struct Notification {
+    message: RwSignal<Option<String>>,
+    actions: RwSignal<Vec<NotificationAction>>,
+}
+
+impl Notification {
+    pub fn new() -> Self {
+        Self {
+            message: RwSignal::new(None),
+            actions: RwSignal::new(vec![]),
+        }
+    }
+
+    // shows the error together with all of its causes
+    pub fn error(&self, error: anyhow::Error) {
+        self.show(format!("{error:#}"), vec![]);
+    }
+
+    // shows the message with a button for every action
+    pub fn show(&self, message: String, actions: Vec<NotificationAction>) {
+        self.message.set(Some(message));
+        self.actions.set(actions);
+    }
+
+    pub fn close(&self) {
+        self.message.set(None);
+        self.actions.set(vec![]);
+    }
+
+    pub fn build_view(&self, my_theme: MyTheme) -> Stack {
+        let notification = *self;
+        let message = self.message;
+        let actions = self.actions;
+        let action_counter = AtomicU32::new(0);
+        h_stack((
+            label(move || message.get().unwrap_or_default()).style(|s| s.flex_grow(1.)),
+            dyn_stack(
+                move || actions.get(),
+                move |_| action_counter.fetch_add(1, Ordering::Relaxed),
+                move |NotificationAction { name, action }| {
+                    button(name).action(move || {
+                        notification.close();
+                        action();
+                    })
+                },
+            )
+            .style(|s| s.flex_direction(FlexDirection::Row).gap(5)),
+            button("x").action(move || notification.close()),
+        ))
+        .style(move |s| {
+            if message.get().is_none() { s.hide() } else { s }
+                .width_full()
+                .padding(5)
+                .gap(5)
+                .items_center()
+                .background(my_theme.error_background)
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use floem::prelude::*;
+use graphyr_core::{
+    cell::CellId,
+    integrity::{self, DanglingArrows, Problem},
+    Data,
+};
+
+use crate::{
+    history::History,
+    notification::{Notification, NotificationAction},
+};
+
+// how many problems are spelled out in the notification, the rest is only counted
+const LISTED_PROBLEMS: usize = 3;
+
+// checks the document and offers to repair it, dangling arrows can be reattached to `owner`
+// (e.g. the cell whose row was removed) or to the highest cell when it's None,
+// returns whether anything was found
+pub fn report_problems(
+    data: RwSignal<Data>,
+    history: History,
+    notification: Notification,
+    owner: Option<CellId>,
+) -> bool {
+    let problems = data.with_untracked(integrity::check);
+    if problems.is_empty() {
+        return false;
+    }
+
+    let repair = move |dangling: DanglingArrows| {
+        move || history.edit(|data| integrity::repair(data, &dangling))
+    };
+    let actions = if problems
+        .iter()
+        .any(|problem| matches!(problem, Problem::DanglingArrow { .. }))
+    {
+        let owner = owner.unwrap_or_else(|| data.with_untracked(|data| data.cell.id.clone()));
+        vec![
+            NotificationAction::new("remove arrows", repair(DanglingArrows::Remove)),
+            NotificationAction::new("reattach arrows", repair(DanglingArrows::Reattach(owner))),
+        ]
+    } else {
+        vec![NotificationAction::new(
+            "repair",
+            repair(DanglingArrows::Remove),
+        )]
+    };
+    notification.show(describe(&problems), actions);
+    true
+}
+
+fn describe(problems: &[Problem]) -> String {
+    let listed = problems
+        .iter()
+        .take(LISTED_PROBLEMS)
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let rest = problems.len().saturating_sub(LISTED_PROBLEMS);
+    format!(
+        "the document has {} problem{}: {listed}{}",
+        problems.len(),
+        if problems.len() > 1 { "s" } else { "" },
+        if rest > 0 {
+            format!(" and {rest} more")
+        } else {
+            String::new()
+        }
+    )
+}
@@ -7,8 +7,9 @@ use floem::{
     text::{fontdb::Source, FONT_SYSTEM},
     views::{ButtonClass, CheckboxClass, TextInputClass},
 };
+use graphyr_core::{color, export::ExportTheme};
 
-const INTER_REGULAR: &[u8] = include_bytes!("../Inter-VariableFont_opsz,wght.ttf");
+pub const INTER_REGULAR: &[u8] = include_bytes!("../Inter-VariableFont_opsz,wght.ttf");
 
 #[derive(Clone)]
 pub struct MyTheme {
@@ -18,6 +19,7 @@ pub struct MyTheme {
     pub secondary_background_hovered: Color,
     pub foreground: Color,
     pub border: Color,
+    pub error_background: Color,
 }
 
 impl Default for MyTheme {
@@ -29,10 +31,34 @@ impl Default for MyTheme {
             secondary_background_hovered: Color::from_rgb8(47, 52, 76),
             foreground: css::WHITE,
             border: css::WHITE,
+            error_background: Color::from_rgb8(92, 26, 34),
         }
     }
 }
 
+impl MyTheme {
+    // colors used when exporting pictures, so they look the same as the application
+    pub fn export_theme(&self) -> ExportTheme {
+        ExportTheme {
+            background: to_core(self.background),
+            secondary_background: to_core(self.secondary_background),
+            foreground: to_core(self.foreground),
+            border: to_core(self.border),
+            error: to_core(self.error_background),
+            font: Some(INTER_REGULAR),
+        }
+    }
+}
+
+pub fn from_core(color: color::Color) -> Color {
+    Color::from_rgba8(color.r, color.g, color.b, color.a)
+}
+
+pub fn to_core(color: Color) -> color::Color {
+    let color = color.to_rgba8();
+    color::Color::rgba(color.r, color.g, color.b, color.a)
+}
+
 pub fn theme(s: Style, my_theme: &MyTheme) -> Style {
     let mut system = FONT_SYSTEM.lock();
     system
@@ -1,6 +1,8 @@
-use floem::prelude::{RwSignal, SignalUpdate};
-
-use crate::data::cell::{CellId, CellPos};
+use floem::prelude::*;
+use graphyr_core::{
+    cell::{CellId, CellPos},
+    Data,
+};
 
 // data used to generate the view, it uses our main centralized data storage
 #[derive(Clone)]
@@ -9,6 +11,8 @@ pub struct ViewData {
     pub displayed_cell: CellPos,
     // temporary value used to determine current arrow creation
     pub arrow_start_id: RwSignal<Option<CellId>>,
+    // (layer, arrow) of the arrow selected on the canvas, its waypoints can be dragged
+    pub selected_arrow: RwSignal<Option<(usize, usize)>>,
 }
 
 impl ViewData {
@@ -16,11 +20,23 @@ impl ViewData {
         Self {
             displayed_cell: CellPos::new(),
             arrow_start_id: RwSignal::new(None),
+            selected_arrow: RwSignal::new(None),
         }
     }
 
     pub fn reset(&mut self) {
         self.displayed_cell = CellPos::new();
         self.arrow_start_id.set(None);
+        self.selected_arrow.set(None);
     }
 }
+
+// position of the cell that is viewed, falls back to the highest one when the position
+// became invalid (e.g. after its row was removed)
+pub fn displayed_cell(data: RwSignal<Data>, view_data: RwSignal<ViewData>) -> Memo<CellPos> {
+    create_memo(move |_| {
+        view_data.with(|view_data| {
+            data.with(|data| data.get_cell_or_highest(&view_data.displayed_cell).1)
+        })
+    })
+}
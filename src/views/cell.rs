@@ -0,0 +1,185 @@
+use floem::{
+    kurbo::Stroke,
+    menu::{Menu, MenuEntry, MenuItem},
+    prelude::*,
+    text::{Attrs, AttrsList, TextLayout},
+};
+use graphyr_core::{cell::CellPos, Data};
+
+use super::table::table_view;
+use crate::{
+    history::History,
+    notification::Notification,
+    problems::report_problems,
+    theme::{to_core, MyTheme},
+    view_data::ViewData,
+};
+
+pub fn cell_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    // position of this cell in the hierarchy
+    pos: CellPos,
+    history: History,
+    notification: Notification,
+    my_theme: MyTheme,
+) -> Stack {
+    let arrow_start_id = view_data.get_untracked().arrow_start_id;
+    // the colors of new arrows must be readable on it
+    let background = to_core(my_theme.background);
+    let show_border = create_memo(move |_| data.with(|data| data.configuration.show_border));
+    let has_table = create_memo({
+        let pos = pos.clone();
+        move |_| data.with(|data| data.get_cell(&pos).is_some_and(|cell| cell.table.is_some()))
+    });
+
+    v_stack((
+        title(data, pos.clone(), history, my_theme.clone()),
+        dyn_container(move || has_table.get(), {
+            let my_theme = my_theme.clone();
+            let pos = pos.clone();
+            move |has_table| {
+                if has_table {
+                    table_view(
+                        data,
+                        view_data,
+                        pos.clone(),
+                        history,
+                        notification,
+                        my_theme.clone(),
+                    )
+                    .into_any()
+                } else {
+                    empty().into_any()
+                }
+            }
+        })
+        .style(move |s| if has_table.get() { s.size_full() } else { s }),
+    ))
+    .style(move |s| {
+        let s = if has_table.get() { s.gap(5.) } else { s };
+        if show_border.get() {
+            s.border(Stroke::new(1.0)).border_color(my_theme.border)
+        } else {
+            s
+        }
+        .items_center()
+        .justify_center()
+        .hover(|s| s.background(my_theme.background_hovered))
+        .size_full()
+        .padding(5.)
+    })
+    .context_menu(move || {
+        let res = Menu::new("");
+        let Some(id) = data.with_untracked(|data| data.get_cell(&pos).map(|cell| cell.id.clone()))
+        else {
+            return res;
+        };
+
+        let res = if !has_table.get() {
+            res.entry(MenuEntry::Item(MenuItem::new("Create table").action({
+                let pos = pos.clone();
+                move || {
+                    history.edit(|data| {
+                        if let Some(cell) = data.get_cell_mut(&pos) {
+                            cell.create_table();
+                        }
+                    })
+                }
+            })))
+        } else {
+            res.entry(MenuEntry::Item(MenuItem::new("Remove table").action({
+                let id = id.clone();
+                let pos = pos.clone();
+                move || {
+                    history.edit(|data| {
+                        if let Some(cell) = data.get_cell_mut(&pos) {
+                            cell.remove_table();
+                        }
+                    });
+                    report_problems(data, history, notification, Some(id.clone()));
+                }
+            })))
+        };
+
+        let displayed_cell = view_data.get_untracked().displayed_cell;
+        let res = if has_table.get() && displayed_cell != pos {
+            res.entry(MenuEntry::Item(MenuItem::new("Open cell").action({
+                let pos = pos.clone();
+                move || view_data.update(|view_data| view_data.displayed_cell = pos.clone())
+            })))
+        } else {
+            res
+        };
+        let res = if let Some(parent) = displayed_cell.parent() {
+            res.entry(MenuEntry::Item(MenuItem::new("Open parent").action(
+                move || view_data.update(|view_data| view_data.displayed_cell = parent.clone()),
+            )))
+        } else {
+            res
+        };
+
+        if let Some(start_id) = arrow_start_id.get() {
+            let res = res.entry(MenuEntry::Item(
+                MenuItem::new("Cancel line start").action(move || arrow_start_id.set(None)),
+            ));
+            if start_id != id {
+                res.entry(MenuEntry::Item(MenuItem::new("End line").action(
+                    move || {
+                        history.edit(|data| {
+                            data.configuration
+                                .add_arrow(start_id.clone(), id.clone(), background)
+                        });
+                        arrow_start_id.set(None)
+                    },
+                )))
+            } else {
+                res
+            }
+        } else {
+            res.entry(MenuEntry::Item(
+                MenuItem::new("Start line").action(move || arrow_start_id.set(Some(id.clone()))),
+            ))
+        }
+    })
+}
+
+fn title(data: RwSignal<Data>, pos: CellPos, history: History, my_theme: MyTheme) -> TextInput {
+    let text = create_memo({
+        let pos = pos.clone();
+        move |_| {
+            data.with(|data| {
+                data.get_cell(&pos)
+                    .map(|cell| cell.title.clone())
+                    .unwrap_or_default()
+            })
+        }
+    });
+    history
+        .text_input(
+            {
+                let pos = pos.clone();
+                move |data| data.get_cell(&pos).map(|cell| cell.title.clone())
+            },
+            move |data, title| {
+                if let Some(cell) = data.get_cell_mut(&pos) {
+                    cell.title = title;
+                }
+            },
+        )
+        .style(move |s| {
+            let font_size = 12.;
+            s.background(my_theme.background)
+                .border_color(Color::TRANSPARENT)
+                .max_width_full()
+                .width({
+                    let mut text_layout = TextLayout::new();
+                    text_layout.set_text(
+                        &text.get(),
+                        AttrsList::new(Attrs::new().font_size(font_size)),
+                    );
+                    text_layout.size().width + 12.
+                })
+                .font_size(font_size)
+        })
+}
@@ -0,0 +1,815 @@
+use std::{fmt::Display, fs};
+
+use anyhow::Context;
+use floem::{
+    action::{open_file, save_as, show_context_menu},
+    event::EventListener,
+    file::{FileDialogOptions, FileSpec},
+    kurbo::Stroke,
+    menu::{Menu, MenuEntry, MenuItem},
+    prelude::*,
+    style::CursorStyle,
+    taffy::FlexDirection,
+};
+use graphyr_core::{
+    cell::CellId,
+    color,
+    configuration::{
+        arrow::{Anchor, Arrow, Head, LineStyle, Routing},
+        layer::Layer,
+        palette::Palette,
+    },
+    export, format, Data,
+};
+
+use crate::{
+    history::History,
+    notification::Notification,
+    problems::report_problems,
+    theme::{from_core, to_core, MyTheme},
+    view_data::ViewData,
+};
+
+// the widths an arrow can be switched between
+const WIDTHS: [f64; 4] = [1., 2., 3., 4.];
+
+pub fn configuration_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    temp_data: RwSignal<Option<Vec<u8>>>,
+    history: History,
+    notification: Notification,
+    my_theme: MyTheme,
+) -> Stack {
+    v_stack((
+        h_stack((
+            "Configuration:".style(|s| s.font_bold().font_size(15)),
+            button("save").on_click_cont({
+                move |_| {
+                    save_as(
+                        FileDialogOptions::new()
+                            .title("Save Configuration")
+                            .default_name("config.ron"),
+                        {
+                            move |file_info| {
+                                if let Some(file) = file_info {
+                                    if let Err(error) =
+                                        format::save_file(&data.get_untracked(), &file.path()[0])
+                                    {
+                                        notification
+                                            .error(error.context("failed to save the document"));
+                                    }
+                                }
+                            }
+                        },
+                    );
+                }
+            }),
+            button("load").on_click_cont(move |_| {
+                open_file(
+                    FileDialogOptions::new()
+                        .title("Load config")
+                        .allowed_types(vec![FileSpec {
+                            name: "config",
+                            extensions: &["ron"],
+                        }]),
+                    {
+                        let temp_data = temp_data.clone();
+                        move |file_info| {
+                            if let Some(file) = file_info {
+                                let path = &file.path()[0];
+                                match fs::read(path)
+                                    .with_context(|| format!("failed to read {}", path.display()))
+                                {
+                                    Ok(buffer) => temp_data.set(Some(buffer)),
+                                    Err(error) => notification.error(error),
+                                }
+                            }
+                        }
+                    },
+                );
+            }),
+        ))
+        .style(|s| s.items_center().gap(5)),
+        h_stack((
+            button("export svg").on_click_cont({
+                let my_theme = my_theme.clone();
+                move |_| {
+                    let my_theme = my_theme.clone();
+                    save_as(
+                        FileDialogOptions::new()
+                            .title("Export SVG")
+                            .default_name("schema.svg")
+                            .allowed_types(vec![FileSpec {
+                                name: "svg",
+                                extensions: &["svg"],
+                            }]),
+                        move |file_info| {
+                            if let Some(file) = file_info {
+                                let pos = view_data.get_untracked().displayed_cell;
+                                if let Err(error) = export::svg::save(
+                                    &data.get_untracked(),
+                                    &pos,
+                                    &my_theme.export_theme(),
+                                    &file.path()[0],
+                                ) {
+                                    notification.error(error.context("failed to export the SVG"));
+                                }
+                            }
+                        },
+                    );
+                }
+            }),
+            button("export png").on_click_cont({
+                let my_theme = my_theme.clone();
+                move |_| {
+                    let my_theme = my_theme.clone();
+                    save_as(
+                        FileDialogOptions::new()
+                            .title("Export PNG")
+                            .default_name("schema.png")
+                            .allowed_types(vec![FileSpec {
+                                name: "png",
+                                extensions: &["png"],
+                            }]),
+                        move |file_info| {
+                            if let Some(file) = file_info {
+                                let pos = view_data.get_untracked().displayed_cell;
+                                if let Err(error) = export::png::save(
+                                    &data.get_untracked(),
+                                    Some(&pos),
+                                    &my_theme.export_theme(),
+                                    2.,
+                                    &file.path()[0],
+                                ) {
+                                    notification.error(error.context("failed to export the PNG"));
+                                }
+                            }
+                        },
+                    );
+                }
+            }),
+            button("check").action(move || {
+                if !report_problems(data, history, notification, None) {
+                    notification.show("the document has no problems".into(), vec![]);
+                }
+            }),
+            button("undo")
+                .action(move || history.undo())
+                .disabled(move || !history.can_undo()),
+            button("redo")
+                .action(move || history.redo())
+                .disabled(move || !history.can_redo()),
+        ))
+        .style(|s| s.items_center().gap(5)),
+        empty(),
+        h_stack((
+            Checkbox::new(move || data.with(|data| data.configuration.show_border)).on_update(
+                move |checked| history.edit(|data| data.configuration.show_border = checked),
+            ),
+            "Show borders",
+        ))
+        .style(|s| s.items_center().gap(5)),
+        empty(),
+        h_stack((
+            Checkbox::new(move || data.with(|data| data.configuration.show_panes)).on_update(
+                move |checked| history.edit(|data| data.configuration.show_panes = checked),
+            ),
+            "Show panes",
+        ))
+        .style(|s| s.items_center().gap(5)),
+        empty(),
+        // new arrows get the same padding every time the document is made again with
+        // the same seed
+        h_stack((
+            Checkbox::new(move || data.with(|data| data.configuration.seed.is_some())).on_update(
+                move |checked| history.edit(|data| data.configuration.seed = checked.then_some(0)),
+            ),
+            "Reproducible arrows",
+            history
+                .text_input(
+                    |data| data.configuration.seed.map(|seed| seed.to_string()),
+                    |data, seed| {
+                        // text that is no number is replaced by the current seed again
+                        if let (Some(current), Ok(seed)) =
+                            (&mut data.configuration.seed, seed.trim().parse())
+                        {
+                            *current = seed;
+                        }
+                    },
+                )
+                .style(move |s| {
+                    let seeded = data.with(|data| data.configuration.seed.is_some());
+                    if seeded {
+                        s.max_width(100)
+                    } else {
+                        s.hide()
+                    }
+                }),
+        ))
+        .style(|s| s.items_center().gap(5)),
+        empty(),
+        h_stack((
+            "Layers:",
+            button("+")
+                .action(move || history.edit(|data| data.configuration.layers.push(Layer::new()))),
+        ))
+        .style(|s| s.items_center().gap(10)),
+        dyn_stack(
+            move || 0..data.with(|data| data.configuration.layers.len()),
+            |i| *i,
+            {
+                let my_theme = my_theme.clone();
+                // the layer whose handle is dragged
+                let dragged = RwSignal::new(None);
+                move |i| layer_view(data, view_data, history, i, my_theme.clone(), dragged)
+            },
+        )
+        .style(|s| {
+            s.flex_direction(FlexDirection::Column)
+                .width_full()
+                .padding(10)
+                .gap(10)
+        })
+        .scroll()
+        .style(move |s| {
+            s.border(Stroke::new(1.0))
+                .border_color(my_theme.border)
+                .padding_right(20)
+        }),
+        empty(),
+    ))
+    .style(move |s| {
+        s.padding(10)
+            .items_center()
+            .border(Stroke::new(1.0))
+            .border_color(my_theme.border)
+            .gap(10)
+    })
+}
+
+// layers and arrows are identified by their index, the views read them from the data,
+// so they stay correct when an earlier one is removed
+//
+// the layers are painted in their order, the handle of a layer is dragged over another one
+// to move it there
+fn layer_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    history: History,
+    i: usize,
+    my_theme: MyTheme,
+    dragged: RwSignal<Option<usize>>,
+) -> Stack {
+    let selected_arrow = view_data.get_untracked().selected_arrow;
+    let arrow_count = create_memo(move |_| {
+        data.with(|data| {
+            data.configuration
+                .layers
+                .get(i)
+                .map_or(0, |layer| layer.arrows.len())
+        })
+    });
+    v_stack((
+        h_stack((
+            "::".draggable()
+                .on_event_cont(EventListener::DragStart, move |_| dragged.set(Some(i)))
+                .style(|s| s.cursor(CursorStyle::Pointer)),
+            Checkbox::new(move || {
+                data.with(|data| {
+                    data.configuration
+                        .layers
+                        .get(i)
+                        .is_some_and(|layer| layer.enabled)
+                })
+            })
+            .on_update(move |checked| {
+                history.edit(|data| {
+                    if let Some(layer) = data.configuration.layers.get_mut(i) {
+                        layer.enabled = checked;
+                    }
+                })
+            }),
+            history
+                .text_input(
+                    move |data| {
+                        data.configuration
+                            .layers
+                            .get(i)
+                            .map(|layer| layer.name.clone())
+                    },
+                    move |data, name| {
+                        if let Some(layer) = data.configuration.layers.get_mut(i) {
+                            layer.name = name;
+                        }
+                    },
+                )
+                .style(|s| s.max_width(70)),
+            // the routing of the arrows that don't choose their own
+            button(label(move || {
+                data.with(|data| {
+                    data.configuration
+                        .layers
+                        .get(i)
+                        .map(|layer| format!("routing: {}", layer.routing))
+                        .unwrap_or_default()
+                })
+            }))
+            .action(move || {
+                history.edit(|data| {
+                    if let Some(layer) = data.configuration.layers.get_mut(i) {
+                        layer.routing = next(&Routing::ALL, layer.routing);
+                    }
+                })
+            }),
+            // arrows between the same two tables share a trunk
+            button(label(move || {
+                data.with(|data| {
+                    data.configuration
+                        .layers
+                        .get(i)
+                        .map(|layer| {
+                            if layer.bundled {
+                                "bundled"
+                            } else {
+                                "unbundled"
+                            }
+                            .to_string()
+                        })
+                        .unwrap_or_default()
+                })
+            }))
+            .action(move || {
+                history.edit(|data| {
+                    if let Some(layer) = data.configuration.layers.get_mut(i) {
+                        layer.bundled = !layer.bundled;
+                    }
+                })
+            }),
+            // the colors of the new arrows
+            button(label(move || {
+                data.with(|data| {
+                    data.configuration
+                        .layers
+                        .get(i)
+                        .map(|layer| format!("palette: {}", layer.palette))
+                        .unwrap_or_default()
+                })
+            }))
+            .action(move || {
+                history.edit(|data| {
+                    if let Some(layer) = data.configuration.layers.get_mut(i) {
+                        layer.palette = next(&Palette::ALL, layer.palette);
+                    }
+                })
+            }),
+            // the indices of the layers after it change, so nothing stays selected
+            button("x").action(move || {
+                selected_arrow.set(None);
+                history.edit(|data| {
+                    if i < data.configuration.layers.len() {
+                        data.configuration.layers.remove(i);
+                    }
+                });
+            }),
+        ))
+        .style(|s| s.items_center().gap(5)),
+        // the indices of the layers change, so nothing stays selected
+        h_stack((
+            button("duplicate").action(move || {
+                selected_arrow.set(None);
+                history.edit(|data| data.configuration.duplicate_layer(i));
+            }),
+            button("merge into")
+                .disabled(move || data.with(|data| data.configuration.layers.len() < 2))
+                .on_click_stop(move |_| {
+                    let layers = data.with_untracked(|data| {
+                        let layers = data.configuration.layers.iter();
+                        layers.map(|l| l.name.clone()).collect::<Vec<_>>()
+                    });
+                    let menu = layers
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(into, _)| *into != i)
+                        .fold(Menu::new(""), |menu, (into, name)| {
+                            menu.entry(MenuEntry::Item(MenuItem::new(name).action(move || {
+                                selected_arrow.set(None);
+                                history.edit(|data| data.configuration.merge_layer(i, into))
+                            })))
+                        });
+                    show_context_menu(menu, None);
+                }),
+            // the arrow selected on the canvas, only one arrow is selected at a time
+            button("move selected arrow here")
+                .action(move || {
+                    if let Some((layer, arrow)) = selected_arrow.get_untracked() {
+                        selected_arrow.set(None);
+                        history.edit(|data| data.configuration.move_arrow(layer, arrow, i));
+                    }
+                })
+                .disabled(move || selected_arrow.get().is_none_or(|(layer, _)| layer == i)),
+        ))
+        .style(|s| s.items_center().gap(5)),
+        dyn_stack(move || 0..arrow_count.get(), |arrow_id| *arrow_id, {
+            let my_theme = my_theme.clone();
+            move |arrow_id| arrow_view(data, view_data, history, i, arrow_id, my_theme.clone())
+        })
+        .style(move |s| {
+            if arrow_count.get() > 0 {
+                s.padding(10).gap(10)
+            } else {
+                s
+            }
+            .flex_direction(FlexDirection::Column)
+        })
+        .scroll()
+        .style(move |s| {
+            if arrow_count.get() > 0 {
+                s.border(Stroke::new(1.0)).border_color(my_theme.border)
+            } else {
+                s
+            }
+            .justify_center()
+            .max_height(250)
+        }),
+    ))
+    .style(move |s| if arrow_count.get() > 0 { s.gap(10) } else { s })
+    .on_event_stop(EventListener::Drop, move |_| {
+        if let Some(layer) = dragged.get_untracked() {
+            dragged.set(None);
+            selected_arrow.set(None);
+            history.edit(|data| data.configuration.move_layer(layer, i));
+        }
+    })
+}
+
+fn arrow_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    history: History,
+    layer: usize,
+    arrow: usize,
+    my_theme: MyTheme,
+) -> Stack {
+    v_stack((
+        h_stack((
+            end_button(data, view_data, layer, arrow, |arrow| &arrow.from),
+            "->",
+            end_button(data, view_data, layer, arrow, |arrow| &arrow.to),
+            // the arrows are painted in this order, the later ones over the earlier ones
+            button("up")
+                .action(move || {
+                    reorder_arrow(
+                        data,
+                        view_data,
+                        history,
+                        layer,
+                        arrow,
+                        arrow.saturating_sub(1),
+                    )
+                })
+                .disabled(move || arrow == 0),
+            button("down")
+                .action(move || reorder_arrow(data, view_data, history, layer, arrow, arrow + 1))
+                .disabled(move || {
+                    data.with(|data| {
+                        data.configuration
+                            .layers
+                            .get(layer)
+                            .is_none_or(|layer| arrow + 1 >= layer.arrows.len())
+                    })
+                }),
+        ))
+        .style(|s| s.gap(5).items_center()),
+        h_stack((
+            button("x").action(move || remove_arrow(data, view_data, history, layer, arrow)),
+            // the label of the arrow, painted along its path
+            history
+                .text_input(
+                    move |data| get_arrow(data, layer, arrow, |arrow| arrow.label.clone()),
+                    move |data, label| {
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            arrow.label = label;
+                        }
+                    },
+                )
+                .placeholder("label")
+                .style(|s| s.max_width(100)),
+            routing_button(data, history, layer, arrow),
+        ))
+        .style(|s| s.gap(5).items_center()),
+        color_picker(data, history, layer, arrow, my_theme),
+        h_stack((
+            // the space kept between the arrow and the other cells
+            label(move || {
+                data.with(|data| get_arrow(data, layer, arrow, |arrow| arrow.padding))
+                    .map(|padding| format!("padding: {padding:.0}"))
+                    .unwrap_or_default()
+            }),
+            button("-").action(move || change_padding(history, layer, arrow, -1.)),
+            button("+").action(move || change_padding(history, layer, arrow, 1.)),
+            // swaps the ends, the heads stay where they are
+            button("reverse").action(move || {
+                history.edit(|data| {
+                    if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                        arrow.reverse();
+                    }
+                })
+            }),
+        ))
+        .style(|s| s.gap(5).items_center()),
+        h_stack((
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "line",
+                &LineStyle::ALL,
+                |arrow| arrow.style,
+                |arrow, style| arrow.style = style,
+            ),
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "width",
+                &WIDTHS,
+                |arrow| arrow.width,
+                |arrow, width| arrow.width = width,
+            ),
+        ))
+        .style(|s| s.gap(5).items_center()),
+        h_stack((
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "start",
+                &Head::ALL,
+                |arrow| arrow.start,
+                |arrow, head| arrow.start = head,
+            ),
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "end",
+                &Head::ALL,
+                |arrow| arrow.end,
+                |arrow, head| arrow.end = head,
+            ),
+        ))
+        .style(|s| s.gap(5).items_center()),
+        h_stack((
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "from",
+                &Anchor::ALL,
+                |arrow| arrow.from_anchor,
+                |arrow, anchor| arrow.from_anchor = anchor,
+            ),
+            cycle_button(
+                data,
+                history,
+                layer,
+                arrow,
+                "to",
+                &Anchor::ALL,
+                |arrow| arrow.to_anchor,
+                |arrow, anchor| arrow.to_anchor = anchor,
+            ),
+        ))
+        .style(|s| s.gap(5).items_center()),
+    ))
+    .style(|s| s.gap(5))
+}
+
+// a button showing a property of the arrow, every click switches it to the next of `values`
+#[allow(clippy::too_many_arguments)]
+fn cycle_button<T: Copy + PartialEq + Display + 'static>(
+    data: RwSignal<Data>,
+    history: History,
+    layer: usize,
+    arrow: usize,
+    name: &'static str,
+    values: &'static [T],
+    get: fn(&Arrow) -> T,
+    set: fn(&mut Arrow, T),
+) -> Button {
+    button(label(move || {
+        data.with(|data| get_arrow(data, layer, arrow, get))
+            .map(|value| format!("{name}: {value}"))
+            .unwrap_or_default()
+    }))
+    .action(move || {
+        history.edit(|data| {
+            if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                set(arrow, next(values, get(arrow)));
+            }
+        })
+    })
+}
+
+// the title of the cell at one end of the arrow, a click shows the table it's in and
+// selects the arrow
+fn end_button(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    layer: usize,
+    arrow: usize,
+    end: fn(&Arrow) -> &CellId,
+) -> Button {
+    let id = move || data.with(|data| get_arrow(data, layer, arrow, |arrow| end(arrow).clone()));
+    button(label(move || {
+        let id = id();
+        data.with(|data| {
+            let title = &data.get_cell(&data.find_pos(&id?)?)?.title;
+            Some(if title.is_empty() {
+                "untitled".to_string()
+            } else {
+                title.clone()
+            })
+        })
+        .unwrap_or_else(|| "missing".into())
+    }))
+    .action(move || {
+        let Some(pos) = id().and_then(|id| data.with_untracked(|data| data.find_pos(&id))) else {
+            return;
+        };
+        view_data.update(|view_data| view_data.displayed_cell = pos.parent().unwrap_or(pos));
+        view_data
+            .get_untracked()
+            .selected_arrow
+            .set(Some((layer, arrow)));
+    })
+    .style(|s| s.max_width(100))
+}
+
+// moves the arrow to another place in its layer, the selection stays on it
+fn reorder_arrow(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    history: History,
+    layer: usize,
+    arrow: usize,
+    to: usize,
+) {
+    let exists = data.with_untracked(|data| {
+        data.configuration
+            .layers
+            .get(layer)
+            .is_some_and(|l| arrow.max(to) < l.arrows.len())
+    });
+    if !exists {
+        return;
+    }
+    history.edit(|data| data.configuration.layers[layer].arrows.swap(arrow, to));
+    let selected_arrow = view_data.get_untracked().selected_arrow;
+    selected_arrow.update(|selected| {
+        if *selected == Some((layer, arrow)) {
+            *selected = Some((layer, to));
+        } else if *selected == Some((layer, to)) {
+            *selected = Some((layer, arrow));
+        }
+    });
+}
+
+// the arrows after it move up, the selection follows them
+fn remove_arrow(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    history: History,
+    layer: usize,
+    arrow: usize,
+) {
+    let exists = data.with_untracked(|data| get_arrow(data, layer, arrow, |_| ()).is_some());
+    if !exists {
+        return;
+    }
+    history.edit(|data| {
+        data.configuration.layers[layer].arrows.remove(arrow);
+    });
+    let selected_arrow = view_data.get_untracked().selected_arrow;
+    selected_arrow.update(|selected| match *selected {
+        Some((l, a)) if l == layer && a == arrow => *selected = None,
+        Some((l, a)) if l == layer && a > arrow => *selected = Some((l, a - 1)),
+        _ => {}
+    });
+}
+
+fn change_padding(history: History, layer: usize, arrow: usize, by: f64) {
+    history.edit(|data| {
+        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+            arrow.padding = (arrow.padding.round() + by).clamp(0., 30.);
+        }
+    })
+}
+
+// the colors of the palette of the layer that can be read on the background, a click gives
+// one to the arrow
+fn color_picker(
+    data: RwSignal<Data>,
+    history: History,
+    layer: usize,
+    arrow: usize,
+    my_theme: MyTheme,
+) -> DynStack<color::Color> {
+    let background = to_core(my_theme.background);
+    let (foreground, unselected) = (my_theme.foreground, my_theme.background);
+    dyn_stack(
+        move || {
+            data.with(|data| {
+                data.configuration
+                    .layers
+                    .get(layer)
+                    .map_or(vec![], |layer| layer.palette.readable(background))
+            })
+        },
+        |color| *color,
+        move |color| {
+            let selected = move || {
+                data.with(|data| get_arrow(data, layer, arrow, |arrow| arrow.color == color))
+                    .unwrap_or(false)
+            };
+            empty()
+                .on_click_stop(move |_| {
+                    history.edit(|data| {
+                        if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                            arrow.color = color;
+                        }
+                    })
+                })
+                .style(move |s| {
+                    let border = if selected() { foreground } else { unselected };
+                    s.size(14, 14)
+                        .background(from_core(color))
+                        .border(Stroke::new(2.0))
+                        .border_color(border)
+                })
+        },
+    )
+    .style(|s| s.gap(3).items_center())
+}
+
+// switches the arrow between following its layer and the routings of its own
+fn routing_button(data: RwSignal<Data>, history: History, layer: usize, arrow: usize) -> Button {
+    const ROUTINGS: [Option<Routing>; 4] = [
+        None,
+        Some(Routing::Direct),
+        Some(Routing::Orthogonal),
+        Some(Routing::Curved),
+    ];
+    button(label(move || {
+        data.with(|data| {
+            let layer = data.configuration.layers.get(layer)?;
+            let arrow = layer.arrows.get(arrow)?;
+            Some(match arrow.routing {
+                Some(routing) => format!("routing: {routing}"),
+                None => format!("routing: layer ({})", layer.routing),
+            })
+        })
+        .unwrap_or_default()
+    }))
+    .action(move || {
+        history.edit(|data| {
+            if let Some(arrow) = get_arrow_mut(data, layer, arrow) {
+                arrow.routing = next(&ROUTINGS, arrow.routing);
+            }
+        })
+    })
+}
+
+// the value after `current`, the first one follows the last one
+fn next<T: Copy + PartialEq>(values: &[T], current: T) -> T {
+    let current = values.iter().position(|value| *value == current);
+    values[current.map_or(0, |current| (current + 1) % values.len())]
+}
+
+pub fn get_arrow<T>(
+    data: &Data,
+    layer: usize,
+    arrow: usize,
+    get: impl FnOnce(&Arrow) -> T,
+) -> Option<T> {
+    data.configuration
+        .layers
+        .get(layer)?
+        .arrows
+        .get(arrow)
+        .map(get)
+}
+
+pub fn get_arrow_mut(data: &mut Data, layer: usize, arrow: usize) -> Option<&mut Arrow> {
+    data.configuration
+        .layers
+        .get_mut(layer)?
+        .arrows
+        .get_mut(arrow)
+}
@@ -0,0 +1,32 @@
+use floem::prelude::*;
+use graphyr_core::Data;
+
+use crate::{
+    breadcrumb::breadcrumb, history::History, main_view::Main, notification::Notification,
+    theme::MyTheme, view_data::ViewData,
+};
+use configuration::configuration_view;
+
+pub mod cell;
+pub mod configuration;
+pub mod table;
+
+// the views are a thin layer over Data, they read it reactively and change it through History
+pub fn data_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    temp_data: RwSignal<Option<Vec<u8>>>,
+    history: History,
+    notification: Notification,
+    my_theme: MyTheme,
+) -> Stack {
+    h_stack((
+        v_stack((
+            breadcrumb(data, view_data, my_theme.clone()),
+            Main::new(data, view_data, history, notification, my_theme.clone())
+                .style(|s| s.size_full()),
+        ))
+        .style(|s| s.size_full()),
+        configuration_view(data, view_data, temp_data, history, notification, my_theme),
+    ))
+}
@@ -0,0 +1,187 @@
+use std::iter;
+
+use floem::{
+    kurbo::Stroke,
+    menu::{Menu, MenuEntry, MenuItem},
+    prelude::*,
+};
+use graphyr_core::{cell::CellPos, table::Cells, Data};
+
+use super::cell::cell_view;
+use crate::{
+    history::History, notification::Notification, problems::report_problems, theme::MyTheme,
+    view_data::ViewData,
+};
+
+// the views of the cells are rebuilt only when the shape of the table changes,
+// everything else is read from the data by the cell views themselves
+pub fn table_view(
+    data: RwSignal<Data>,
+    view_data: RwSignal<ViewData>,
+    // position of the cell that owns this table
+    pos: CellPos,
+    history: History,
+    notification: Notification,
+    my_theme: MyTheme,
+) -> DynamicContainer<(usize, usize, bool)> {
+    let shape = create_memo({
+        let pos = pos.clone();
+        move |_| {
+            data.with(|data| {
+                let (rows, cols) = data
+                    .get_cell(&pos)
+                    .and_then(|cell| cell.table.as_ref())
+                    .map_or((0, 0), |table| (table.cells.rows(), table.cells.cols()));
+                (rows, cols, data.configuration.show_panes)
+            })
+        }
+    });
+
+    dyn_container(
+        move || shape.get(),
+        move |(rows, cols, show_panes)| {
+            v_stack_from_iter(
+                iter::once(if show_panes {
+                    h_stack_from_iter((0..cols).map(|i| {
+                        // this is a column pane, in the first of the rows
+                        create_pane(
+                            my_theme.clone(),
+                            data,
+                            history,
+                            notification,
+                            pos.clone(),
+                            true,
+                            i,
+                            rows,
+                            cols,
+                        )
+                    }))
+                    .into_any()
+                } else {
+                    empty().into_any()
+                })
+                .chain((0..rows).map(|i| {
+                    h_stack_from_iter(
+                        iter::once(if show_panes {
+                            // this is a row pane, first of the columns
+                            create_pane(
+                                my_theme.clone(),
+                                data,
+                                history,
+                                notification,
+                                pos.clone(),
+                                false,
+                                i,
+                                rows,
+                                cols,
+                            )
+                            .into_any()
+                        } else {
+                            empty().into_any()
+                        })
+                        .chain((0..cols).map(|col_id| {
+                            cell_view(
+                                data,
+                                view_data,
+                                pos.child(i, col_id),
+                                history,
+                                notification,
+                                my_theme.clone(),
+                            )
+                            .into_any()
+                        })),
+                    )
+                    .style(|s| s.size_full())
+                    .into_any()
+                })),
+            )
+            .style(|s| s.size_full())
+        },
+    )
+    .style(|s| s.size_full())
+}
+
+fn create_pane(
+    my_theme: MyTheme,
+    data: RwSignal<Data>,
+    history: History,
+    notification: Notification,
+    pos: CellPos,
+    column: bool,
+    index: usize,
+    rows: usize,
+    cols: usize,
+) -> Empty {
+    let size = 20;
+    empty()
+        .context_menu(move || {
+            let res = Menu::new("").entry(MenuEntry::SubMenu(
+                Menu::new("Add")
+                    .entry(MenuEntry::Item(MenuItem::new("PRE").action({
+                        let pos = pos.clone();
+                        move || {
+                            edit_cells(history, &pos, |cells| {
+                                if column {
+                                    cells.add_col(index);
+                                } else {
+                                    cells.add_row(index);
+                                }
+                            })
+                        }
+                    })))
+                    .entry(MenuEntry::Item(MenuItem::new("POST").action({
+                        let pos = pos.clone();
+                        move || {
+                            edit_cells(history, &pos, |cells| {
+                                if column {
+                                    cells.add_col(index + 1);
+                                } else {
+                                    cells.add_row(index + 1);
+                                }
+                            })
+                        }
+                    }))),
+            ));
+            if if column { cols } else { rows } > 1 {
+                res.entry(MenuEntry::Item(MenuItem::new("Remove").action({
+                    let pos = pos.clone();
+                    move || {
+                        edit_cells(history, &pos, |cells| {
+                            if column {
+                                cells.remove_col(index);
+                            } else {
+                                cells.remove_row(index);
+                            }
+                        });
+                        // arrows of the removed cells can be moved to the owner of the table
+                        let owner = data
+                            .with_untracked(|data| data.get_cell(&pos).map(|cell| cell.id.clone()));
+                        report_problems(data, history, notification, owner);
+                    }
+                })))
+            } else {
+                res
+            }
+        })
+        .style(move |s| {
+            if column {
+                if index == 0 { s.margin_left(size) } else { s }
+                    .min_height(size)
+                    .width_full()
+            } else {
+                s.min_width(size).height_full()
+            }
+            .background(my_theme.secondary_background)
+            .border(Stroke::new(1.0))
+            .border_color(my_theme.border)
+        })
+}
+
+// changes the cells of the table owned by the cell at `pos`
+fn edit_cells(history: History, pos: &CellPos, edit: impl FnOnce(&mut Cells)) {
+    history.edit(|data| {
+        if let Some(table) = data.get_cell_mut(pos).and_then(|cell| cell.table.as_mut()) {
+            edit(&mut table.cells);
+        }
+    });
+}